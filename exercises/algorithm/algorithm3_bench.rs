@@ -0,0 +1,15 @@
+// Sorts `n` numbers in no particular order
+fn workload(n: usize) -> Vec<u32> {
+    let mut seed = 0x2545_f491_u32;
+    let mut array: Vec<u32> = (0..n)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        })
+        .collect();
+    sort(&mut array);
+    assert!(array.windows(2).all(|pair| pair[0] <= pair[1]));
+    array
+}
@@ -0,0 +1,19 @@
+// Inserts `n` numbers in no particular order, then looks each of them up.
+// Sorted numbers would turn the tree into a list.
+fn workload(n: usize) -> BinarySearchTree<u32> {
+    let mut seed = 0x2545_f491_u32;
+    let values: Vec<u32> = (0..n)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        })
+        .collect();
+    let mut bst = BinarySearchTree::new();
+    for &value in &values {
+        bst.insert(value);
+    }
+    assert!(values.iter().all(|&value| bst.search(value)));
+    bst
+}
@@ -0,0 +1,15 @@
+// Adds `n` numbers to a min-heap, then takes them all out again
+fn workload(n: usize) -> Vec<u32> {
+    let mut seed = 0x2545_f491_u32;
+    let mut heap = MinHeap::new();
+    for _ in 0..n {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        heap.add(seed);
+    }
+    let sorted: Vec<u32> = heap.collect();
+    assert_eq!(sorted.len(), n);
+    assert!(sorted.windows(2).all(|pair| pair[0] <= pair[1]));
+    sorted
+}
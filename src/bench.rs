@@ -0,0 +1,292 @@
+use serde::Deserialize;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// How many times the workload runs for each size. The fastest run counts,
+// the others were slowed down by something else.
+pub const REPEATS: usize = 3;
+// How much faster than the expected complexity class the running time may
+// grow, as an exponent of the size, before it's considered too slow.
+// Timings are noisy, and telling `n` from `n log n` takes more than a few sizes.
+const GROWTH_TOLERANCE: f64 = 0.5;
+
+// The settings of a `bench` exercise.
+// This is deserialized from `[exercises.bench]` in info.toml
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BenchOptions {
+    // The file defining `fn workload(n: usize)`, which runs the exercise on
+    // an input of size `n`. It sees all the items of the exercise, and may
+    // return what it computed so the optimizer can't skip the work.
+    pub workload: PathBuf,
+    // The sizes the workload runs with
+    #[serde(default = "default_sizes")]
+    pub sizes: Vec<usize>,
+    // How many milliseconds the workload may take at any of the sizes
+    pub budget_ms: Option<u64>,
+    // How the running time may grow with the size
+    pub complexity: Option<Complexity>,
+}
+
+fn default_sizes() -> Vec<usize> {
+    vec![1_000, 10_000, 100_000]
+}
+
+// A complexity class, like `n log n`
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Complexity {
+    #[serde(rename = "1")]
+    Constant,
+    #[serde(rename = "log n")]
+    Logarithmic,
+    #[serde(rename = "n")]
+    Linear,
+    #[serde(rename = "n log n")]
+    Linearithmic,
+    #[serde(rename = "n^2")]
+    Quadratic,
+    #[serde(rename = "n^3")]
+    Cubic,
+}
+
+impl Complexity {
+    // The growth of the class at `n`, up to a constant factor
+    fn at(self, n: f64) -> f64 {
+        let n = n.max(2.0);
+        match self {
+            Complexity::Constant => 1.0,
+            Complexity::Logarithmic => n.ln(),
+            Complexity::Linear => n,
+            Complexity::Linearithmic => n * n.ln(),
+            Complexity::Quadratic => n * n,
+            Complexity::Cubic => n * n * n,
+        }
+    }
+}
+
+impl Display for Complexity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let class = match self {
+            Complexity::Constant => "1",
+            Complexity::Logarithmic => "log n",
+            Complexity::Linear => "n",
+            Complexity::Linearithmic => "n log n",
+            Complexity::Quadratic => "n^2",
+            Complexity::Cubic => "n^3",
+        };
+        f.write_str(class)
+    }
+}
+
+// The program timing the workload: the exercise, with the workload in a
+// module of its own so it doesn't clash with the exercise's items. Each
+// argument is a size to run the workload with, and each run prints the size
+// and how many nanoseconds it took.
+// The files are included relative to the driver, which is written to the
+// current directory.
+pub fn driver(exercise: &Path, workload: &Path) -> String {
+    format!(
+        r#"#![allow(dead_code, unused)]
+
+include!("{}");
+
+mod rustlings_bench {{
+    use super::*;
+
+    include!("{}");
+
+    pub fn time(n: usize) -> u128 {{
+        let start = std::time::Instant::now();
+        std::hint::black_box(workload(std::hint::black_box(n)));
+        start.elapsed().as_nanos()
+    }}
+}}
+
+fn main() {{
+    for size in std::env::args().skip(1) {{
+        let n = size.parse().unwrap();
+        println!("{{n}} {{}}", rustlings_bench::time(n));
+    }}
+}}
+"#,
+        include_path(exercise),
+        include_path(workload)
+    )
+}
+
+fn include_path(path: &Path) -> String {
+    path.display()
+        .to_string()
+        .replace('\\', "/")
+        .replace('"', "\\\"")
+}
+
+// The arguments of the driver: every size, `REPEATS` times
+pub fn driver_args(options: &BenchOptions) -> Vec<String> {
+    options
+        .sizes
+        .iter()
+        .flat_map(|size| std::iter::repeat(size.to_string()).take(REPEATS))
+        .collect()
+}
+
+// How long the driver may run before some run certainly went over the
+// budget, with a second to spare for starting up. Without a budget, a slow
+// solution runs until it's done.
+pub fn time_limit(options: &BenchOptions) -> Option<Duration> {
+    let runs = (options.sizes.len() * REPEATS) as u32;
+    let budget = Duration::from_millis(options.budget_ms?);
+    Some(budget * runs + Duration::from_secs(1))
+}
+
+// The fastest run of each size, from the output of the driver
+pub fn timings(output: &str) -> Vec<(usize, Duration)> {
+    let mut timings: Vec<(usize, Duration)> = Vec::new();
+    for line in output.lines() {
+        let mut parts = line.split_whitespace().map(str::parse::<u64>);
+        let (Some(Ok(size)), Some(Ok(nanos))) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let (size, time) = (size as usize, Duration::from_nanos(nanos));
+        match timings.iter_mut().find(|(s, _)| *s == size) {
+            Some((_, fastest)) => *fastest = (*fastest).min(time),
+            None => timings.push((size, time)),
+        }
+    }
+    timings
+}
+
+// A table of the timings
+pub fn report(timings: &[(usize, Duration)]) -> String {
+    let mut report = format!("{}\n", tr!("bench.timings"));
+    for (size, time) in timings {
+        report.push_str(&format!(
+            "{size:>12}  {:>10.3} ms\n",
+            time.as_secs_f64() * 1000.0
+        ));
+    }
+    report
+}
+
+// Check the timings against the budget and the complexity class,
+// explaining what's too slow
+pub fn check(options: &BenchOptions, timings: &[(usize, Duration)]) -> Result<(), String> {
+    if let Some(budget) = options.budget_ms.map(Duration::from_millis) {
+        if let Some((size, time)) = timings.iter().find(|(_, time)| *time > budget) {
+            return Err(tr!(
+                "bench.over_budget",
+                size,
+                format!("{:.1} ms", time.as_secs_f64() * 1000.0),
+                format!("{} ms", budget.as_millis())
+            ));
+        }
+    }
+    if let Some(complexity) = options.complexity {
+        // How much faster than the class the time grows, as an exponent
+        let excess = slope(timings, |n| complexity.at(n));
+        if excess.is_some_and(|excess| excess > GROWTH_TOLERANCE) {
+            let measured = slope(timings, |_| 1.0).unwrap_or_default();
+            return Err(tr!("bench.too_slow", complexity, format!("{measured:.1}")));
+        }
+    }
+    Ok(())
+}
+
+// The slope of the time divided by `class` over the size, on a log-log scale,
+// fitted with least squares. It's about `k` when that ratio grows like `n^k`.
+fn slope(timings: &[(usize, Duration)], class: impl Fn(f64) -> f64) -> Option<f64> {
+    let points: Vec<(f64, f64)> = timings
+        .iter()
+        .map(|(size, time)| {
+            let n = *size as f64;
+            let time = time.as_secs_f64().max(1e-9);
+            (n.ln(), (time / class(n)).ln())
+        })
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if variance == 0.0 {
+        return None;
+    }
+    Some(covariance / variance)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn options(budget_ms: Option<u64>, complexity: Option<Complexity>) -> BenchOptions {
+        BenchOptions {
+            workload: PathBuf::from("workload.rs"),
+            sizes: default_sizes(),
+            budget_ms,
+            complexity,
+        }
+    }
+
+    // Timings growing exactly like `n^k`, one microsecond at size 1000
+    fn growing(k: i32) -> Vec<(usize, Duration)> {
+        default_sizes()
+            .into_iter()
+            .map(|n| (n, Duration::from_secs_f64(1e-6 * (n as f64 / 1000.0).powi(k))))
+            .collect()
+    }
+
+    #[test]
+    fn test_timings_keep_fastest_run() {
+        let timings = timings("1000 300\n1000 200\n10000 4000\nwarning: noise\n10000 3000\n");
+        assert_eq!(
+            timings,
+            [
+                (1000, Duration::from_nanos(200)),
+                (10000, Duration::from_nanos(3000))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complexity() {
+        let linearithmic = options(None, Some(Complexity::Linearithmic));
+        assert!(check(&linearithmic, &growing(1)).is_ok());
+        let error = check(&linearithmic, &growing(2)).unwrap_err();
+        assert!(error.contains("n log n"));
+        assert!(error.contains("2.0"));
+        assert!(check(&options(None, Some(Complexity::Quadratic)), &growing(2)).is_ok());
+    }
+
+    #[test]
+    fn test_budget() {
+        // The largest size takes 10 ms
+        let timings = growing(2);
+        assert!(check(&options(Some(20), None), &timings).is_ok());
+        assert!(check(&options(Some(5), None), &timings)
+            .unwrap_err()
+            .contains("100000"));
+    }
+
+    #[test]
+    fn test_time_limit() {
+        assert_eq!(time_limit(&options(None, None)), None);
+        // Three runs for each of the three sizes
+        assert_eq!(
+            time_limit(&options(Some(100), None)),
+            Some(Duration::from_millis(1900))
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let options: BenchOptions =
+            toml::from_str("workload = \"w.rs\"\ncomplexity = \"n log n\"\nbudget_ms = 100").unwrap();
+        assert_eq!(options.complexity, Some(Complexity::Linearithmic));
+        assert_eq!(options.sizes, default_sizes());
+        assert!(toml::from_str::<BenchOptions>("workload = \"w.rs\"\ncomplexity = \"n!\"").is_err());
+    }
+}
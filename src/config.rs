@@ -0,0 +1,310 @@
+use crate::i18n::Lang;
+use console::Term;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+// The name of the configuration file, both in the rustlings directory and in
+// the home directory
+pub const CONFIG_FILE: &str = ".rustlings.toml";
+
+// When to color the output
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+// The settings of a configuration file. Unset settings fall back to the
+// other file, and then to the defaults.
+#[derive(Deserialize, Serialize, Default, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // Decorate messages with emoji, unless NO_EMOJI is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+    // When to color the output, `auto` colors it on terminals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorChoice>,
+    // Show the output of passing exercises, like `--nocapture`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verbose: Option<bool>,
+    // Show the hint of an exercise once it passes, like `watch --success-hints`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_hints: Option<bool>,
+    // How many seconds an exercise may run before it's stopped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    // How many exercises `cicvverify` checks at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    // The language of the messages, like `--lang`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    // The command watch mode opens exercises with, like `code`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+}
+
+// The names of the settings, in the order `config list` shows them
+pub const KEYS: [&str; 8] = [
+    "emoji",
+    "color",
+    "verbose",
+    "success_hints",
+    "timeout",
+    "jobs",
+    "lang",
+    "editor",
+];
+
+// Where a configuration file lives
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scope {
+    // In the home directory, for all rustlings directories
+    Global,
+    // In the rustlings directory
+    Local,
+}
+
+impl Scope {
+    pub fn path(self) -> Option<PathBuf> {
+        match self {
+            Scope::Global => home::home_dir().map(|home| home.join(CONFIG_FILE)),
+            Scope::Local => Some(PathBuf::from(CONFIG_FILE)),
+        }
+    }
+}
+
+impl ColorChoice {
+    pub fn parse(choice: &str) -> Option<ColorChoice> {
+        match choice {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let choice = match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        };
+        f.write_str(choice)
+    }
+}
+
+impl Config {
+    // Read a configuration file, which doesn't have to exist
+    pub fn read(scope: Scope) -> Result<Config, String> {
+        let Some(path) = scope.path() else {
+            return Ok(Config::default());
+        };
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(tr!("config.unreadable", path.display(), e)),
+        };
+        let config: Config =
+            toml::from_str(&source).map_err(|e| tr!("config.unparsable", path.display(), e))?;
+        config
+            .validate()
+            .map_err(|e| tr!("config.invalid", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn write(&self, scope: Scope) -> Result<PathBuf, String> {
+        let path = scope
+            .path()
+            .ok_or_else(|| tr!("config.no_home").to_string())?;
+        let source = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(&path, source).map_err(|e| tr!("config.unwritable", path.display(), e))?;
+        Ok(path)
+    }
+
+    // The settings of the home directory, overridden by those of the
+    // rustlings directory
+    pub fn load() -> Result<Config, String> {
+        Ok(Config::read(Scope::Global)?.merge(Config::read(Scope::Local)?))
+    }
+
+    // Take the settings of `other` that are set, and the rest from `self`
+    fn merge(self, other: Config) -> Config {
+        Config {
+            emoji: other.emoji.or(self.emoji),
+            color: other.color.or(self.color),
+            verbose: other.verbose.or(self.verbose),
+            success_hints: other.success_hints.or(self.success_hints),
+            timeout: other.timeout.or(self.timeout),
+            jobs: other.jobs.or(self.jobs),
+            lang: other.lang.or(self.lang),
+            editor: other.editor.or(self.editor),
+        }
+    }
+
+    // Check what the types of the settings can't express
+    fn validate(&self) -> Result<(), String> {
+        if let Some(lang) = &self.lang {
+            if Lang::parse(lang).is_none() {
+                return Err(tr!("config.unsupported_lang", lang));
+            }
+        }
+        if self.jobs == Some(0) || self.timeout == Some(0) {
+            return Err(tr!("config.at_least_one").to_string());
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, String> {
+        check_key(key)?;
+        let table = toml::Value::try_from(self).map_err(|e| e.to_string())?;
+        Ok(table.get(key).map(|value| match value {
+            toml::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }))
+    }
+
+    // Change a setting, checking that the value fits it
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        check_key(key)?;
+        let mut table = match toml::Value::try_from(&*self) {
+            Ok(toml::Value::Table(table)) => table,
+            _ => toml::value::Table::new(),
+        };
+        // Take the value as a boolean or number if it looks like one,
+        // and as a string otherwise
+        let parsed = value
+            .parse()
+            .map(toml::Value::Boolean)
+            .or_else(|_| value.parse().map(toml::Value::Integer))
+            .ok();
+        let mut config = None;
+        for candidate in parsed.into_iter().chain([toml::Value::String(value.to_string())]) {
+            table.insert(key.to_string(), candidate);
+            if let Ok(parsed) = toml::Value::Table(table.clone()).try_into::<Config>() {
+                config = Some(parsed);
+                break;
+            }
+        }
+        let config = config.ok_or_else(|| tr!("config.invalid_value", value, key))?;
+        config.validate()?;
+        *self = config;
+        Ok(())
+    }
+}
+
+fn check_key(key: &str) -> Result<(), String> {
+    if KEYS.contains(&key) {
+        Ok(())
+    } else {
+        Err(tr!("config.unknown_key", key, KEYS.join(", ")))
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static COLORS: OnceLock<bool> = OnceLock::new();
+
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+// The merged configuration of the running command
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+// Settle whether to color the output, from `--color`, then NO_COLOR, then the
+// configuration, and otherwise whether stdout is a terminal that supports it.
+// The choice applies to rustlings' own output and to the compilers it runs.
+pub fn init_colors(flag: Option<ColorChoice>) {
+    let no_color = env::var_os("NO_COLOR")
+        .filter(|value| !value.is_empty())
+        .map(|_| ColorChoice::Never);
+    let choice = flag
+        .or(no_color)
+        .or(get().color)
+        .unwrap_or(ColorChoice::Auto);
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => Term::stdout().features().colors_supported(),
+    };
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+    let _ = COLORS.set(enabled);
+}
+
+pub fn colors() -> bool {
+    COLORS.get().copied().unwrap_or(false)
+}
+
+// Whether to decorate messages with emoji
+pub fn emoji() -> bool {
+    env::var("NO_EMOJI").is_err() && get().emoji.unwrap_or(true)
+}
+
+// How long an exercise may run before it's stopped, if it may be stopped
+pub fn timeout() -> Option<Duration> {
+    get().timeout.map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let global: Config = toml::from_str("emoji = false\njobs = 4\neditor = \"code\"").unwrap();
+        let local: Config = toml::from_str("jobs = 2\ncolor = \"never\"").unwrap();
+        let config = global.merge(local);
+        assert_eq!(config.emoji, Some(false));
+        assert_eq!(config.jobs, Some(2));
+        assert_eq!(config.color, Some(ColorChoice::Never));
+        assert_eq!(config.editor.as_deref(), Some("code"));
+        assert_eq!(config.timeout, None);
+    }
+
+    #[test]
+    fn test_color_choice() {
+        for choice in [ColorChoice::Auto, ColorChoice::Always, ColorChoice::Never] {
+            assert_eq!(ColorChoice::parse(&choice.to_string()), Some(choice));
+        }
+        assert_eq!(ColorChoice::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn test_unknown_setting() {
+        assert!(toml::from_str::<Config>("emojis = false").is_err());
+        assert!(Config::default().get("emojis").is_err());
+    }
+
+    #[test]
+    fn test_set_and_get() {
+        let mut config = Config::default();
+        config.set("timeout", "10").unwrap();
+        config.set("emoji", "false").unwrap();
+        config.set("editor", "code --wait").unwrap();
+        config.set("lang", "zh-CN").unwrap();
+        assert_eq!(config.timeout, Some(10));
+        assert_eq!(config.get("emoji").unwrap().as_deref(), Some("false"));
+        assert_eq!(config.get("editor").unwrap().as_deref(), Some("code --wait"));
+        assert_eq!(config.get("jobs").unwrap(), None);
+
+        assert!(config.set("timeout", "soon").is_err());
+        assert!(config.set("color", "sometimes").is_err());
+        assert!(config.set("lang", "xx").is_err());
+        assert!(config.set("jobs", "0").is_err());
+        assert_eq!(config.timeout, Some(10));
+    }
+}
@@ -0,0 +1,561 @@
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+// The severity of a compiler diagnostic
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+    Help,
+    FailureNote,
+    // Internal compiler errors and anything rustc adds in the future
+    #[serde(other)]
+    Other,
+}
+
+// A structured compiler message, built from the JSON emitted by
+// `rustc --error-format=json` or `cargo --message-format=json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    // How severe the diagnostic is
+    pub level: Level,
+    // The error code (`E0382`) or lint name (`clippy::float_cmp`), if any
+    pub code: Option<String>,
+    // The main message of the diagnostic
+    pub message: String,
+    // The location the diagnostic points at
+    pub primary_span: Option<Span>,
+    // Secondary locations with an explanatory label
+    pub labels: Vec<Span>,
+    // Notes and help messages that are not attached to a location
+    pub notes: Vec<String>,
+    // Code changes suggested by the compiler
+    pub suggestions: Vec<Suggestion>,
+}
+
+// A location in an exercise's source code
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    // The first source line covered by the span
+    pub source: Option<String>,
+    pub label: Option<String>,
+}
+
+// A replacement the compiler suggests for a span
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+    // Whether the suggestion can be applied without human review
+    pub machine_applicable: bool,
+}
+
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    code: Option<RawCode>,
+    level: Level,
+    spans: Vec<RawSpan>,
+    children: Vec<RawDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RawCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    text: Vec<RawText>,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawText {
+    text: String,
+}
+
+// A line of `cargo --message-format=json` output
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RawDiagnostic>,
+}
+
+// Split compiler output into the diagnostics it contains and
+// the remaining plain text lines (linker errors, test harness output, ...).
+// Both bare rustc diagnostics and cargo's `compiler-message` records
+// are understood. Relative file names are resolved against `root`.
+pub fn parse(output: &str, root: &Path) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut rest = String::new();
+
+    for line in output.lines() {
+        let raw = if line.starts_with('{') {
+            if let Ok(message) = serde_json::from_str::<CargoMessage>(line) {
+                if message.reason != "compiler-message" {
+                    continue;
+                }
+                message.message
+            } else {
+                serde_json::from_str::<RawDiagnostic>(line).ok()
+            }
+        } else {
+            None
+        };
+
+        match raw {
+            Some(raw) => {
+                if !is_noise(&raw) {
+                    diagnostics.push(Diagnostic::from_raw(raw, root));
+                }
+            }
+            None => {
+                rest.push_str(line);
+                rest.push('\n');
+            }
+        }
+    }
+
+    (diagnostics, rest)
+}
+
+// Summary messages like "aborting due to 2 previous errors" repeat
+// what the other diagnostics already say
+fn is_noise(raw: &RawDiagnostic) -> bool {
+    raw.level == Level::FailureNote
+        || (raw.code.is_none()
+            && raw.spans.is_empty()
+            && (raw.message.starts_with("aborting due to")
+                || raw.message.ends_with("warnings emitted")
+                || raw.message.ends_with("warning emitted")))
+}
+
+// Resolve the `..` components of a path that has them after a directory,
+// like the paths of packages generated in a hidden directory
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(normalized.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+impl Span {
+    fn from_raw(raw: &RawSpan, root: &Path) -> Span {
+        let file_name = if Path::new(&raw.file_name).is_relative() {
+            normalize(&root.join(&raw.file_name)).display().to_string()
+        } else {
+            raw.file_name.clone()
+        };
+        Span {
+            file_name,
+            line_start: raw.line_start,
+            line_end: raw.line_end,
+            column_start: raw.column_start,
+            column_end: raw.column_end,
+            source: raw.text.first().map(|t| t.text.clone()),
+            label: raw.label.clone(),
+        }
+    }
+}
+
+impl Diagnostic {
+    fn from_raw(raw: RawDiagnostic, root: &Path) -> Diagnostic {
+        let primary_span = raw
+            .spans
+            .iter()
+            .find(|s| s.is_primary)
+            .map(|s| Span::from_raw(s, root));
+        let labels = raw
+            .spans
+            .iter()
+            .filter(|s| !s.is_primary && s.label.is_some())
+            .map(|s| Span::from_raw(s, root))
+            .collect();
+
+        let mut notes = Vec::new();
+        let mut suggestions = Vec::new();
+        for child in &raw.children {
+            let replacements: Vec<&RawSpan> = child
+                .spans
+                .iter()
+                .filter(|s| s.suggested_replacement.is_some())
+                .collect();
+            if replacements.is_empty() {
+                notes.push(format!("{}: {}", child.level.as_str(), child.message));
+            }
+            for span in replacements {
+                suggestions.push(Suggestion {
+                    message: child.message.clone(),
+                    span: Span::from_raw(span, root),
+                    replacement: span.suggested_replacement.clone().unwrap_or_default(),
+                    machine_applicable: span.suggestion_applicability.as_deref()
+                        == Some("MachineApplicable"),
+                });
+            }
+        }
+
+        Diagnostic {
+            level: raw.level,
+            code: raw.code.map(|c| c.code),
+            message: raw.message,
+            primary_span,
+            labels,
+            notes,
+            suggestions,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.level, Level::Error | Level::Other)
+    }
+
+    // Whether the diagnostic is a clippy lint, as opposed to a compiler error
+    pub fn is_lint(&self) -> bool {
+        self.code
+            .as_deref()
+            .is_some_and(|code| code.starts_with("clippy::"))
+    }
+
+    // Render the diagnostic as a short excerpt of the exercise
+    pub fn render(&self) -> String {
+        if self.is_lint() {
+            return self.render_lint();
+        }
+        let header = match &self.code {
+            Some(code) => format!("{}[{}]", self.level.as_str(), code),
+            None => self.level.as_str().to_string(),
+        };
+        let mut out = self.render_excerpt(header);
+        for suggestion in &self.suggestions {
+            render_help(&mut out, suggestion);
+        }
+        for note in &self.notes {
+            out.push_str(&format!("  {} {note}\n", style("=").blue().bold()));
+        }
+        out
+    }
+
+    // Render a clippy lint by its name and message next to the offending line.
+    // The fixes clippy is sure about are shown as a diff of that line, and the
+    // notes pointing at the lint's documentation or at how it was enabled are
+    // left out.
+    fn render_lint(&self) -> String {
+        let code = self.code.clone().unwrap_or_default();
+        let mut out = self.render_excerpt(code);
+        for suggestion in &self.suggestions {
+            match (&suggestion.span.source, suggestion.machine_applicable) {
+                (Some(source), true) if suggestion.span.line_start == suggestion.span.line_end => {
+                    out.push_str(&format!(
+                        "  {} {}:\n",
+                        style("help:").cyan().bold(),
+                        suggestion.message
+                    ));
+                    out.push_str(&format!("  {}\n", style(format!("- {}", source.trim())).red()));
+                    for line in suggestion.apply().lines() {
+                        out.push_str(&format!("  {}\n", style(format!("+ {line}")).green()));
+                    }
+                }
+                _ => render_help(&mut out, suggestion),
+            }
+        }
+        for note in self.notes.iter().filter(|note| !is_lint_boilerplate(note)) {
+            out.push_str(&format!("  {} {note}\n", style("=").blue().bold()));
+        }
+        out
+    }
+
+    // The header, location and source lines of the diagnostic
+    fn render_excerpt(&self, header: String) -> String {
+        let mut out = String::new();
+        let header = if self.is_error() {
+            style(header).red().bold()
+        } else {
+            style(header).yellow().bold()
+        };
+        out.push_str(&format!("{header}: {}\n", style(&self.message).bold()));
+
+        if let Some(span) = &self.primary_span {
+            out.push_str(&format!(
+                "  {} {}:{}:{}\n",
+                style("-->").blue().bold(),
+                span.file_name,
+                span.line_start,
+                span.column_start
+            ));
+        }
+        let mut spans: Vec<&Span> = self.primary_span.iter().chain(&self.labels).collect();
+        spans.sort_by_key(|s| (s.line_start, s.column_start));
+        let mut rest = &spans[..];
+        while let Some(first) = rest.first() {
+            let count = rest
+                .iter()
+                .take_while(|s| s.line_start == first.line_start)
+                .count();
+            render_line(&mut out, &rest[..count]);
+            rest = &rest[count..];
+        }
+        out
+    }
+}
+
+fn render_help(out: &mut String, suggestion: &Suggestion) {
+    out.push_str(&format!(
+        "  {} {}: `{}`\n",
+        style("help:").cyan().bold(),
+        suggestion.message,
+        suggestion.apply()
+    ));
+}
+
+// The notes clippy attaches to every lint: the link to its documentation and
+// the flag or attribute that enabled it
+fn is_lint_boilerplate(note: &str) -> bool {
+    note.contains("for further information visit")
+        || note.contains("requested on the command line")
+        || note.contains("implied by")
+        || note.contains("to override")
+        || note.contains("on by default")
+}
+
+impl Suggestion {
+    // The source line with the suggested replacement applied
+    pub fn apply(&self) -> String {
+        let source = self.span.source.clone().unwrap_or_default();
+        if self.span.line_start != self.span.line_end {
+            return self.replacement.clone();
+        }
+        let chars: Vec<char> = source.chars().collect();
+        let start = self.span.column_start.saturating_sub(1).min(chars.len());
+        let end = self.span.column_end.saturating_sub(1).clamp(start, chars.len());
+        let before: String = chars[..start].iter().collect();
+        let after: String = chars[end..].iter().collect();
+        format!("{before}{}{after}", self.replacement).trim().to_string()
+    }
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+            Level::Help => "help",
+            Level::FailureNote => "failure-note",
+            Level::Other => "error",
+        }
+    }
+}
+
+// A source line, followed by the carets and label of every span starting on it
+fn render_line(out: &mut String, spans: &[&Span]) {
+    let Some(source) = spans.iter().find_map(|s| s.source.as_ref()) else {
+        return;
+    };
+    let number = spans[0].line_start.to_string();
+    let gutter = " ".repeat(number.len());
+    out.push_str(&format!(
+        "{} {} {source}\n",
+        style(&number).blue().bold(),
+        style("|").blue().bold()
+    ));
+    for span in spans {
+        let start = span.column_start.saturating_sub(1);
+        let width = if span.line_start == span.line_end {
+            span.column_end.saturating_sub(span.column_start).max(1)
+        } else {
+            source.chars().count().saturating_sub(start).max(1)
+        };
+        let label = match span.label.as_deref() {
+            Some(label) if !label.is_empty() => format!(" {label}"),
+            _ => String::new(),
+        };
+        out.push_str(&format!(
+            "{gutter} {} {}{}{label}\n",
+            style("|").blue().bold(),
+            " ".repeat(start),
+            style("^".repeat(width)).red().bold()
+        ));
+    }
+}
+
+// Render a concise summary of the diagnostics of a failed compilation.
+// When there are errors, warnings are only counted to keep the output short.
+pub fn render(diagnostics: &[Diagnostic]) -> String {
+    let errors: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.is_error()).collect();
+    let warnings = diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning)
+        .count();
+
+    let shown: Vec<&Diagnostic> = if errors.is_empty() {
+        diagnostics.iter().collect()
+    } else {
+        errors.clone()
+    };
+    let mut out = String::new();
+    for diagnostic in shown {
+        out.push_str(&diagnostic.render());
+        out.push('\n');
+    }
+
+    let lints = errors.iter().filter(|d| d.is_lint()).count();
+    let mut summary = Vec::new();
+    if errors.len() > lints {
+        let count = errors.len() - lints;
+        summary.push(plural(
+            count,
+            tr!("diagnostics.error"),
+            tr!("diagnostics.errors"),
+        ));
+    }
+    if lints > 0 {
+        summary.push(plural(
+            lints,
+            tr!("diagnostics.lint"),
+            tr!("diagnostics.lints"),
+        ));
+    }
+    if warnings > 0 {
+        summary.push(plural(
+            warnings,
+            tr!("diagnostics.warning"),
+            tr!("diagnostics.warnings"),
+        ));
+    }
+    if !summary.is_empty() {
+        out.push_str(&summary.join(", "));
+        if !errors.is_empty() && warnings > 0 {
+            out.push_str(tr!("diagnostics.warnings_hidden"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// Fill in the message for one thing or for several, depending on `count`
+fn plural(count: usize, one: &str, many: &str) -> String {
+    let template = if count == 1 { one } else { many };
+    template.replace("{0}", &count.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LEN_ZERO: &str = r#"{"$message_type":"diagnostic","message":"length comparison to zero","code":{"code":"clippy::len_zero","explanation":null},"level":"error","spans":[{"file_name":"../c.rs","byte_start":122,"byte_end":134,"line_start":7,"line_end":7,"column_start":13,"column_end":25,"is_primary":true,"text":[{"text":"    let n = v.len() == 0;","highlight_start":13,"highlight_end":25}],"label":null,"suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#len_zero","code":null,"level":"help","spans":[],"children":[],"rendered":null},{"message":"`-D clippy::len-zero` implied by `-D warnings`","code":null,"level":"note","spans":[],"children":[],"rendered":null},{"message":"using `is_empty` is clearer and more explicit","code":null,"level":"help","spans":[{"file_name":"../c.rs","byte_start":122,"byte_end":134,"line_start":7,"line_end":7,"column_start":13,"column_end":25,"is_primary":true,"text":[{"text":"    let n = v.len() == 0;","highlight_start":13,"highlight_end":25}],"label":null,"suggested_replacement":"v.is_empty()","suggestion_applicability":"MachineApplicable","expansion":null}],"children":[],"rendered":null}],"rendered":"error: length comparison to zero"}"#;
+    const MOVED: &str = r#"{"$message_type":"diagnostic","message":"cannot assign twice to immutable variable `x`","code":{"code":"E0384","explanation":null},"level":"error","spans":[{"file_name":"m.rs","byte_start":81,"byte_end":82,"line_start":5,"line_end":5,"column_start":9,"column_end":10,"is_primary":false,"text":[{"text":"    let x = 5;","highlight_start":9,"highlight_end":10}],"label":"first assignment to `x`","suggested_replacement":null,"suggestion_applicability":null,"expansion":null},{"file_name":"m.rs","byte_start":92,"byte_end":97,"line_start":6,"line_end":6,"column_start":5,"column_end":10,"is_primary":true,"text":[{"text":"    x = 6;","highlight_start":5,"highlight_end":10}],"label":"cannot assign twice to immutable variable","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"consider making this binding mutable","code":null,"level":"help","spans":[{"file_name":"m.rs","byte_start":81,"byte_end":81,"line_start":5,"line_end":5,"column_start":9,"column_end":9,"is_primary":true,"text":[{"text":"    let x = 5;","highlight_start":9,"highlight_end":9}],"label":null,"suggested_replacement":"mut ","suggestion_applicability":"MachineApplicable","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0384]"}"#;
+
+    const ABORTING: &str = r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting"}"#;
+
+    #[test]
+    fn test_parse_rustc_diagnostic() {
+        let output = format!("{MOVED}\n{ABORTING}\nnote: some linker output\n");
+        let (diagnostics, rest) = parse(&output, Path::new("exercises"));
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.level, Level::Error);
+        assert_eq!(diagnostic.code.as_deref(), Some("E0384"));
+        let span = diagnostic.primary_span.as_ref().unwrap();
+        assert_eq!(span.file_name, "exercises/m.rs");
+        assert_eq!((span.line_start, span.column_start), (6, 5));
+        assert_eq!(diagnostic.labels.len(), 1);
+        assert_eq!(diagnostic.suggestions.len(), 1);
+        assert!(diagnostic.suggestions[0].machine_applicable);
+        assert_eq!(diagnostic.suggestions[0].apply(), "let mut x = 5;");
+        assert_eq!(rest, "note: some linker output\n");
+    }
+
+    #[test]
+    fn test_parse_cargo_message() {
+        let output = format!(
+            "{{\"reason\":\"compiler-artifact\",\"package_id\":\"x\"}}\n{{\"reason\":\"compiler-message\",\"message\":{MOVED}}}\n"
+        );
+        let (diagnostics, rest) = parse(&output, Path::new(""));
+        assert_eq!(diagnostics.len(), 1);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let path = Path::new(".rustlings/clippy/clippy1/../../../exercises/clippy/clippy1.rs");
+        assert_eq!(normalize(path), Path::new("exercises/clippy/clippy1.rs"));
+        assert_eq!(normalize(Path::new("../exercises/./m.rs")), Path::new("../exercises/m.rs"));
+    }
+
+    #[test]
+    fn test_render_points_at_line() {
+        let (diagnostics, _) = parse(MOVED, Path::new(""));
+        let rendered = console::strip_ansi_codes(&render(&diagnostics)).to_string();
+        assert!(rendered.contains("error[E0384]: cannot assign twice"));
+        assert!(rendered.contains("--> m.rs:6:5"));
+        assert!(rendered.contains("6 |     x = 6;"));
+        assert!(rendered.contains("^^^^^ cannot assign twice to immutable variable"));
+    }
+
+    #[test]
+    fn test_render_spans_on_one_line() {
+        let span = |column_start, column_end, label: Option<&str>| Span {
+            file_name: "m.rs".into(),
+            line_start: 3,
+            line_end: 3,
+            column_start,
+            column_end,
+            source: Some("    let n: u8 = \"one\";".into()),
+            label: label.map(String::from),
+        };
+        let diagnostic = Diagnostic {
+            level: Level::Error,
+            code: Some("E0308".into()),
+            message: "mismatched types".into(),
+            primary_span: Some(span(17, 22, Some("expected `u8`, found `&str`"))),
+            labels: vec![span(12, 14, Some("expected due to this")), span(0, 1, Some(""))],
+            notes: Vec::new(),
+            suggestions: Vec::new(),
+        };
+        let rendered = console::strip_ansi_codes(&diagnostic.render()).to_string();
+        assert_eq!(rendered.matches("let n: u8").count(), 1);
+        assert!(rendered.contains(
+            "3 |     let n: u8 = \"one\";\n  | ^\n  |            ^^ expected due to this\n  |                 ^^^^^ expected `u8`, found `&str`\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_lint_as_diff() {
+        let (diagnostics, _) = parse(LEN_ZERO, Path::new("pkg"));
+        assert!(diagnostics[0].is_lint());
+        let rendered = console::strip_ansi_codes(&render(&diagnostics)).to_string();
+        assert!(rendered.starts_with("clippy::len_zero: length comparison to zero\n  --> c.rs:7:13\n"));
+        assert!(rendered.contains("  - let n = v.len() == 0;\n  + let n = v.is_empty();\n"));
+        assert!(!rendered.contains("further information"));
+        assert!(!rendered.contains("implied by"));
+        assert!(rendered.ends_with("1 lint\n"));
+    }
+}
@@ -1,18 +1,42 @@
+use crate::bench::{self, BenchOptions};
+use crate::config;
+use crate::diagnostics::{self, Diagnostic};
+use crate::expected::{self, ExpectedOutput};
+use crate::i18n;
+use crate::toolchain::{self, Version};
+use crate::worker;
+use crate::workspace;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::env;
+use std::error::Error;
 use std::fmt::{self, Display, Formatter};
 use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Output};
 
-const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
-const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format=json"];
+const CARGO_JSON_ARGS: &[&str] = &["--message-format=json"];
+// The edition exercises are compiled with, unless they pick another one
+pub const EDITION: &str = "2021";
+const EDITIONS: &[&str] = &["2015", "2018", "2021", "2024"];
+// The lints clippy exercises are checked for, unless they pick others
+const DEFAULT_CLIPPY_LINTS: &[(&str, LintLevel)] = &[("clippy::float_cmp", LintLevel::Deny)];
 const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
-const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
+// The manifests older versions of rustlings compiled clippy and build script exercises with
+const LEGACY_CARGO_TOML_PATHS: &[&str] =
+    &["exercises/clippy/Cargo.toml", "exercises/tests/Cargo.toml"];
+pub const INFO_TOML_PATH: &str = "info.toml";
+
+// Color the output of the compilers and test harnesses like rustlings' own
+fn color_args() -> [&'static str; 2] {
+    ["--color", if config::colors() { "always" } else { "never" }]
+}
 
 // Get a temporary file name that is hopefully unique
 #[inline]
@@ -25,8 +49,23 @@ fn temp_file() -> String {
     format!("./temp_{}_{thread_id}", process::id())
 }
 
+// The temporary program timing the workload of a bench exercise, and its source
+fn temp_bench() -> String {
+    format!("{}_bench", temp_file())
+}
+
+fn temp_bench_source() -> String {
+    format!("{}_bench.rs", temp_file())
+}
+
+// The temporary library the doc examples of an exercise are linked with.
+// Rustc only links libraries named like `lib*.rlib`.
+fn temp_lib() -> String {
+    format!("./lib{}.rlib", temp_file().trim_start_matches("./"))
+}
+
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -37,6 +76,18 @@ pub enum Mode {
     Clippy,
     // Indicates that the exercise should be run using cargo with build script
     BuildScript,
+    // Indicates that the exercise should be compiled as a binary whose output
+    // is compared to the expected one
+    Output,
+    // Indicates that the exercise should fail to compile with the expected errors
+    #[serde(rename = "compile_fail")]
+    CompileFail,
+    // Indicates that the exercise should be compiled as a library whose doc
+    // examples are run as tests
+    Doctest,
+    // Indicates that the exercise should be tested, and then timed on a
+    // workload of increasing sizes
+    Bench,
 }
 
 #[derive(Deserialize)]
@@ -44,9 +95,54 @@ pub struct ExerciseList {
     pub exercises: Vec<Exercise>,
 }
 
+impl ExerciseList {
+    // Read and parse the exercise list, usually from info.toml
+    pub fn from_file(path: impl AsRef<Path>) -> Result<ExerciseList, Box<dyn Error>> {
+        let toml_str = fs::read_to_string(path)?;
+        let list = toml::from_str::<ExerciseList>(&toml_str)?;
+        list.validate()?;
+        Ok(list)
+    }
+
+    // Check what deserializing can't: the settings some modes require, and
+    // the exercises the diagnostic hints refer to
+    fn validate(&self) -> Result<(), String> {
+        for exercise in &self.exercises {
+            if exercise.mode == Mode::Bench && exercise.build.bench.is_none() {
+                return Err(tr!("exercise.no_workload", exercise.name));
+            }
+            if exercise.mode == Mode::CompileFail && exercise.expected.expected_errors.is_empty() {
+                return Err(tr!("exercise.no_expected_errors", exercise.name));
+            }
+            // The test harness would take them as test filters
+            let runs_tests = matches!(exercise.mode, Mode::Test | Mode::Bench);
+            if runs_tests && !exercise.build.args.is_empty() {
+                return Err(tr!("exercise.args_for_tests", exercise.name));
+            }
+            let unknown = exercise
+                .diagnostic_hints
+                .iter()
+                .filter_map(|hint| hint.see.as_ref())
+                .find(|see| !self.exercises.iter().any(|e| &&e.name == see));
+            if let Some(see) = unknown {
+                return Err(tr!("exercise.unknown_see", exercise.name, see));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Whether the manifest at `path` was written by rustlings itself while
+// compiling an exercise, as opposed to being maintained by the exercise author.
+// The packages are now generated in `.rustlings`, but older versions of
+// rustlings left these behind in the exercises.
+pub fn is_generated_manifest(path: &Path) -> bool {
+    LEGACY_CARGO_TOML_PATHS.iter().any(|legacy| path.ends_with(legacy))
+}
+
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -54,8 +150,117 @@ pub struct Exercise {
     pub path: PathBuf,
     // The mode of the exercise (Test, Compile, or Clippy)
     pub mode: Mode,
-    // The hint text associated with the exercise
+    // The hint text associated with the exercise,
+    // either a string or a table of translations keyed by language
+    #[serde(deserialize_with = "i18n::localized")]
     pub hint: String,
+    // Hints shown when compiling the exercise produces specific diagnostics
+    #[serde(default)]
+    pub diagnostic_hints: Vec<DiagnosticHint>,
+    // How the exercise is built, beyond its mode
+    #[serde(flatten)]
+    pub build: BuildOptions,
+    // What an `output` exercise must print
+    #[serde(flatten)]
+    pub expected: ExpectedOutput,
+}
+
+// Settings for building and running an exercise that most exercises leave out
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct BuildOptions {
+    // The oldest Rust release the exercise compiles with, like "1.70"
+    #[serde(default)]
+    pub min_rust_version: Option<Version>,
+    // The edition to compile the exercise with instead of the default one
+    #[serde(default, deserialize_with = "edition")]
+    pub edition: Option<String>,
+    // More flags to compile the exercise with
+    #[serde(default)]
+    pub rustc_args: Vec<String>,
+    // Conditional compilation options to set, like `feature="foo"`
+    #[serde(default)]
+    pub cfg: Vec<String>,
+    // Environment variables for compiling and running the exercise
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    // The command-line arguments the exercise runs with
+    #[serde(default)]
+    pub args: Vec<String>,
+    // What the exercise reads from its standard input. Without it, the
+    // standard input is empty.
+    #[serde(default)]
+    pub stdin: Option<Stdin>,
+    // The workload and the limits of a `bench` exercise
+    #[serde(default)]
+    pub bench: Option<BenchOptions>,
+    // The level of each clippy lint the exercise is about, like
+    // `{ "clippy::float_cmp" = "deny" }`, replacing the default lints
+    #[serde(default)]
+    pub clippy_lints: Option<BTreeMap<String, LintLevel>>,
+}
+
+// The standard input of an exercise, either given inline like `stdin = "42"`
+// or read from a file like `stdin = { file = "exercises/io/numbers.txt" }`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Stdin {
+    Text(String),
+    File { file: PathBuf },
+}
+
+// The level of a lint, as set with `-A`, `-W`, `-D` and `-F`
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
+impl LintLevel {
+    fn flag(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "-A",
+            LintLevel::Warn => "-W",
+            LintLevel::Deny => "-D",
+            LintLevel::Forbid => "-F",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+            LintLevel::Forbid => "forbid",
+        }
+    }
+}
+
+fn edition<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let edition = String::deserialize(deserializer)?;
+    if EDITIONS.contains(&edition.as_str()) {
+        Ok(Some(edition))
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "unknown edition '{edition}', expected one of {}",
+            EDITIONS.join(", ")
+        )))
+    }
+}
+
+// A targeted hint for a compiler error code or lint.
+// This is deserialized from `[[exercises.diagnostic_hints]]` in info.toml
+#[derive(Deserialize, Debug, Clone)]
+pub struct DiagnosticHint {
+    // The error code (`E0382`) or lint name (`clippy::float_cmp`) triggering the hint
+    pub code: String,
+    // The hint text shown next to the compiler output, translated like the exercise hint
+    #[serde(deserialize_with = "i18n::localized")]
+    pub hint: String,
+    // The name of an earlier exercise that teaches the concept
+    pub see: Option<String>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -99,6 +304,24 @@ pub struct ExerciseOutput {
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // The compiler diagnostics found in the output, if it was a compilation
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ExerciseOutput {
+    // Collect the diagnostics of a rustc or cargo invocation that emitted JSON,
+    // leaving the remaining plain text in stdout and stderr
+    fn from_compiler(stdout: &[u8], stderr: &[u8], root: &Path) -> ExerciseOutput {
+        let (mut diagnostics, stdout) = diagnostics::parse(&String::from_utf8_lossy(stdout), root);
+        let (stderr_diagnostics, stderr) =
+            diagnostics::parse(&String::from_utf8_lossy(stderr), root);
+        diagnostics.extend(stderr_diagnostics);
+        ExerciseOutput {
+            stdout,
+            stderr,
+            diagnostics,
+        }
+    }
 }
 
 struct FileHandle;
@@ -110,85 +333,152 @@ impl Drop for FileHandle {
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    pub fn compile(&self) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        if let Err(message) = self.check_rust_version() {
+            return Err(ExerciseOutput {
+                stdout: String::new(),
+                stderr: message,
+                diagnostics: Vec::new(),
+            });
+        }
+
         let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
+            Mode::Doctest => worker::output(
+                toolchain::rustc()
+                    .args(["--crate-type", "lib", "--crate-name", &self.crate_name()])
+                    .args([self.path.to_str().unwrap(), "-o", &temp_lib()])
+                    .args(RUSTC_JSON_ARGS)
+                    .args(self.rustc_flags())
+                    .args(color_args())
+                    .envs(&self.build.env),
+            ),
+            Mode::Compile | Mode::Output | Mode::CompileFail => worker::output(
+                toolchain::rustc()
+                    .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args(RUSTC_JSON_ARGS)
+                    .args(self.rustc_flags())
+                    .args(color_args())
+                    .envs(&self.build.env),
+            ),
+            Mode::Test => worker::output(
+                toolchain::rustc()
+                    .args(["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args(RUSTC_JSON_ARGS)
+                    .args(self.rustc_flags())
+                    .args(color_args())
+                    .envs(&self.build.env),
+            ),
+            // The tests, and the optimized driver of the workload once they compile
+            Mode::Bench => worker::output(
+                toolchain::rustc()
+                    .args(["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
+                    .args(RUSTC_JSON_ARGS)
+                    .args(self.rustc_flags())
+                    .args(color_args())
+                    .envs(&self.build.env),
+            )
+            .and_then(|tests| {
+                if !tests.status.success() {
+                    return Ok(tests);
+                }
+                let options = self.bench_options();
+                fs::write(temp_bench_source(), bench::driver(&self.path, &options.workload))?;
+                worker::output(
+                    toolchain::rustc()
+                        .args([&temp_bench_source(), "-o", &temp_bench(), "-O"])
+                        .args(RUSTC_JSON_ARGS)
+                        .args(self.rustc_flags())
+                        .args(color_args())
+                        .envs(&self.build.env),
+                )
+            }),
             Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                let package = workspace::package(self)
+                    .expect("Failed to generate the package of the clippy exercise");
+                let manifest = package.join("Cargo.toml");
+                let target_dir = package.join("target");
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(RUSTC_EDITION_ARGS)
-                    .output()
-                    .expect("Failed to compile!");
-                // Due to an issue with Clippy, a cargo clean is required to catch all lints.
-                // See https://github.com/rust-lang/rust-clippy/issues/2604
-                // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
-                // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .output()
+                worker::output(
+                    toolchain::rustc()
+                        .args([self.path.to_str().unwrap(), "-o", &temp_file()])
+                        .args(RUSTC_JSON_ARGS)
+                        .args(self.rustc_flags())
+                        .args(color_args())
+                        .envs(&self.build.env),
+                )
+                .expect("Failed to compile!");
+                // Cargo decides what to rebuild from modification times, which
+                // can miss quick successive saves. Relint the exercise whenever
+                // its contents or the way it's linted changed.
+                let fingerprint = self.clippy_fingerprint();
+                let fingerprint_path = package.join("fingerprint");
+                if fs::read_to_string(&fingerprint_path).ok() != Some(fingerprint.clone()) {
+                    worker::output(
+                        toolchain::cargo()
+                            .args(["clean", "--package", &self.name, "--manifest-path"])
+                            .arg(&manifest)
+                            .arg("--target-dir")
+                            .arg(&target_dir)
+                            .args(color_args()),
+                    )
                     .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
-                    .output()
-            },
+                    let _ = fs::write(&fingerprint_path, fingerprint);
+                }
+                // The flags after `--` are passed to clippy for the exercise,
+                // like they are to rustc in the other modes
+                worker::output(
+                    toolchain::cargo()
+                        .args(["clippy", "--manifest-path"])
+                        .arg(&manifest)
+                        .arg("--target-dir")
+                        .arg(&target_dir)
+                        .args(CARGO_JSON_ARGS)
+                        .args(color_args())
+                        .arg("--")
+                        .args(self.cfg_flags())
+                        .args(&self.build.rustc_args)
+                        .args(self.clippy_lint_flags())
+                        .envs(&self.build.env),
+                )
+            }
             Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
-
-                Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
-                    .output()
+                let package = workspace::package(self)
+                    .expect("Failed to generate the package of the build script exercise");
+                let manifest = package.join("Cargo.toml");
+                let target_dir = package.join("target");
+                // The build script may print something different on every
+                // run, like a timestamp, but Cargo only runs it again when its
+                // package changed. Start over so it runs on every verification.
+                worker::output(
+                    toolchain::cargo()
+                        .args(["clean", "--package", &self.name, "--manifest-path"])
+                        .arg(&manifest)
+                        .arg("--target-dir")
+                        .arg(&target_dir)
+                        .args(color_args()),
+                )
+                .expect("Failed to run 'cargo clean'");
+                worker::output(
+                    toolchain::cargo()
+                        .args(["test", "--manifest-path"])
+                        .arg(&manifest)
+                        .arg("--target-dir")
+                        .arg(&target_dir)
+                        .args(CARGO_JSON_ARGS)
+                        .args(color_args())
+                        .envs(self.rustflags())
+                        .envs(&self.build.env),
+                )
             }
         }
         .expect("Failed to run 'compile' command.");
 
+        if self.mode == Mode::CompileFail {
+            return self.expect_compile_failure(cmd);
+        }
         if cmd.status.success() {
             Ok(CompiledExercise {
                 exercise: self,
@@ -196,37 +486,295 @@ path = "{}.rs""#,
             })
         } else {
             clean();
-            Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-            })
+            Err(ExerciseOutput::from_compiler(
+                &cmd.stdout,
+                &cmd.stderr,
+                &self.compiler_root(),
+            ))
+        }
+    }
+
+    // A `compile_fail` exercise compiles as expected when the compiler reports
+    // all the expected errors. Other errors are allowed next to them.
+    fn expect_compile_failure(&self, cmd: Output) -> Result<CompiledExercise<'_>, ExerciseOutput> {
+        clean();
+        let codes = self.expected.expected_errors.join(", ");
+        if cmd.status.success() {
+            return Err(ExerciseOutput {
+                stdout: String::new(),
+                stderr: tr!("verify.compiled_without_errors", self, codes),
+                diagnostics: Vec::new(),
+            });
+        }
+        let mut output = ExerciseOutput::from_compiler(&cmd.stdout, &cmd.stderr, &self.compiler_root());
+        let missing = self.expected.expected_errors.iter().any(|code| {
+            !output
+                .diagnostics
+                .iter()
+                .any(|d| d.is_error() && d.code.as_ref() == Some(code))
+        });
+        if missing {
+            output.stdout = tr!("verify.missing_errors", self, codes);
+            return Err(output);
+        }
+        Ok(CompiledExercise {
+            exercise: self,
+            _handle: FileHandle,
+        })
+    }
+
+    // The edition the exercise is compiled with
+    pub fn edition(&self) -> &str {
+        self.build.edition.as_deref().unwrap_or(EDITION)
+    }
+
+    fn cfg_flags(&self) -> Vec<String> {
+        self.build
+            .cfg
+            .iter()
+            .flat_map(|cfg| ["--cfg".to_string(), cfg.clone()])
+            .collect()
+    }
+
+    // The flags rustc compiles the exercise with, besides the output options
+    fn rustc_flags(&self) -> Vec<String> {
+        let mut flags = vec!["--edition".to_string(), self.edition().to_string()];
+        flags.extend(self.cfg_flags());
+        flags.extend(self.build.rustc_args.iter().cloned());
+        flags
+    }
+
+    // The lint levels clippy checks the exercise with. Warnings always fail
+    // the exercise, the exercise picks the lints on top.
+    pub fn clippy_lints(&self) -> Vec<(&str, LintLevel)> {
+        match &self.build.clippy_lints {
+            Some(lints) => lints
+                .iter()
+                .map(|(lint, level)| (lint.as_str(), *level))
+                .collect(),
+            None => DEFAULT_CLIPPY_LINTS.to_vec(),
+        }
+    }
+
+    fn clippy_lint_flags(&self) -> Vec<String> {
+        let mut flags = vec!["-D".to_string(), "warnings".to_string()];
+        for (lint, level) in self.clippy_lints() {
+            flags.push(level.flag().to_string());
+            flags.push(lint.to_string());
+        }
+        flags
+    }
+
+    // A hash of what linting the exercise depends on: its source, the flags
+    // and the toolchain
+    fn clippy_fingerprint(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        fs::read(&self.path).unwrap_or_default().hash(&mut hasher);
+        self.cfg_flags().hash(&mut hasher);
+        self.build.rustc_args.hash(&mut hasher);
+        self.build.env.hash(&mut hasher);
+        self.clippy_lint_flags().hash(&mut hasher);
+        self.edition().hash(&mut hasher);
+        toolchain::toolchain().hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    // Cargo has no arguments for rustc flags when building and running
+    // tests, so they are passed through RUSTFLAGS, after the learner's own
+    fn rustflags(&self) -> Option<(&'static str, String)> {
+        let mut flags = self.cfg_flags();
+        flags.extend(self.build.rustc_args.iter().cloned());
+        if flags.is_empty() {
+            return None;
+        }
+        let mut rustflags = env::var("RUSTFLAGS").unwrap_or_default();
+        for flag in flags {
+            if !rustflags.is_empty() {
+                rustflags.push(' ');
+            }
+            rustflags.push_str(&flag);
+        }
+        Some(("RUSTFLAGS", rustflags))
+    }
+
+    // Check that the rustc in use is recent enough for the exercise.
+    // When its version can't be told, compiling the exercise tells instead.
+    fn check_rust_version(&self) -> Result<(), String> {
+        match (self.build.min_rust_version, toolchain::rustc_version()) {
+            (Some(required), Some(found)) if found < required => {
+                Err(tr!("verify.rust_too_old", self, required, found))
+            }
+            _ => Ok(()),
         }
     }
 
     fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
+        let mut command = match self.mode {
+            Mode::Doctest => self.rustdoc_test(),
+            _ => Command::new(&temp_file()),
+        };
+        match self.mode {
+            Mode::Test | Mode::Bench => {
+                command.arg("--show-output").args(color_args());
+            }
             Mode::BuildScript => return Ok(ExerciseOutput {
                 stdout: "".to_string(),
                 stderr: "".to_string(),
+                diagnostics: Vec::new(),
+            }),
+            Mode::CompileFail => return Ok(ExerciseOutput {
+                stdout: String::new(),
+                stderr: String::new(),
+                diagnostics: Vec::new(),
             }),
-            _ => "",
+            Mode::Compile | Mode::Clippy | Mode::Output | Mode::Doctest => {}
+        }
+        if self.mode == Mode::Doctest {
+            // Rustdoc passes these on to the test harness
+            command.args(self.build.args.iter().flat_map(|arg| ["--test-args", arg]));
+        } else {
+            command.args(&self.build.args);
+        }
+        command.envs(&self.build.env);
+        let input = match &self.build.stdin {
+            None => None,
+            Some(Stdin::Text(text)) => Some(text.clone().into_bytes()),
+            Some(Stdin::File { file }) => match fs::read(file) {
+                Ok(input) => Some(input),
+                Err(e) => {
+                    return Err(ExerciseOutput {
+                        stdout: String::new(),
+                        stderr: tr!("run.stdin_unreadable", file.display(), e),
+                        diagnostics: Vec::new(),
+                    })
+                }
+            },
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
+        let cmd = execute(&mut command, input.as_deref())?;
 
         let output = ExerciseOutput {
             stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
             stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+            diagnostics: Vec::new(),
         };
 
-        if cmd.status.success() {
-            Ok(output)
-        } else {
-            Err(output)
+        if !cmd.status.success() {
+            if self.mode == Mode::Doctest {
+                return Err(ExerciseOutput {
+                    stderr: failing_doc_examples(&output.stdout) + &output.stderr,
+                    ..output
+                });
+            }
+            return Err(output);
+        }
+        if self.mode == Mode::Bench {
+            return self.run_bench(output);
+        }
+        if self.mode == Mode::Output {
+            let checked = self
+                .expected_output()
+                .and_then(|expected| expected::check(&expected, &output.stdout, self.expected.output_match));
+            if let Err(mismatch) = checked {
+                return Err(ExerciseOutput {
+                    stdout: String::new(),
+                    stderr: format!("{mismatch}{}", output.stderr),
+                    diagnostics: Vec::new(),
+                });
+            }
+        }
+        Ok(output)
+    }
+
+    // Time the workload of a bench exercise whose tests passed, and check
+    // the timings. They are reported after the output of the tests.
+    fn run_bench(&self, tests: ExerciseOutput) -> Result<ExerciseOutput, ExerciseOutput> {
+        let options = self.bench_options();
+        let mut command = Command::new(temp_bench());
+        command.args(bench::driver_args(options)).envs(&self.build.env);
+        let cmd = match bench::time_limit(options) {
+            Some(limit) if config::timeout().map_or(true, |timeout| limit < timeout) => {
+                match worker::output_with(&mut command, None, Some(limit)) {
+                    Ok(cmd) => cmd,
+                    Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+                        let budget = format!("{} ms", options.budget_ms.unwrap_or_default());
+                        return Err(ExerciseOutput {
+                            stderr: tr!("bench.stopped", budget),
+                            ..tests
+                        });
+                    }
+                    Err(e) => panic!("Failed to run 'run' command: {e}"),
+                }
+            }
+            _ => execute(&mut command, None)?,
+        };
+        let stdout = String::from_utf8_lossy(&cmd.stdout);
+        if !cmd.status.success() {
+            return Err(ExerciseOutput {
+                stdout: stdout.to_string(),
+                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+                diagnostics: Vec::new(),
+            });
+        }
+        let timings = bench::timings(&stdout);
+        let output = ExerciseOutput {
+            stdout: format!("{}{}", tests.stdout, bench::report(&timings)),
+            ..tests
+        };
+        match bench::check(options, &timings) {
+            Ok(()) => Ok(output),
+            Err(reason) => Err(ExerciseOutput {
+                stderr: reason,
+                ..output
+            }),
+        }
+    }
+
+    fn bench_options(&self) -> &BenchOptions {
+        self.build
+            .bench
+            .as_ref()
+            .expect("bench exercises have their options checked when info.toml is read")
+    }
+
+    // Run the doc examples of the exercise, linked with its compiled library
+    fn rustdoc_test(&self) -> Command {
+        let name = self.crate_name();
+        let mut command = toolchain::rustdoc();
+        command
+            .args(["--test", self.path.to_str().unwrap(), "--crate-name", &name])
+            .arg("--extern")
+            .arg(format!("{name}={}", temp_lib()))
+            .args(self.rustc_flags())
+            .args(color_args());
+        command
+    }
+
+    // The name of the library a doctest exercise is compiled to
+    fn crate_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    // The output an `output` exercise must print
+    fn expected_output(&self) -> Result<String, String> {
+        if let Some(expected) = &self.expected.expected_output {
+            return Ok(expected.clone());
+        }
+        let path = self.path.with_extension("expected");
+        fs::read_to_string(&path)
+            .map_err(|_| tr!("run.no_expected_output", self, path.display()))
+    }
+
+    // The directory compiler diagnostics are relative to.
+    // Cargo reports paths relative to the package of the exercise.
+    fn compiler_root(&self) -> PathBuf {
+        match self.mode {
+            Mode::Clippy | Mode::BuildScript => workspace::package_dir(self),
+            Mode::Compile
+            | Mode::Test
+            | Mode::Output
+            | Mode::CompileFail
+            | Mode::Doctest
+            | Mode::Bench => PathBuf::new(),
         }
     }
 
@@ -271,6 +819,14 @@ path = "{}.rs""#,
         State::Pending(context)
     }
 
+    // The targeted hints matching the codes of the given diagnostics
+    pub fn diagnostic_hints_for(&self, diagnostics: &[Diagnostic]) -> Vec<&DiagnosticHint> {
+        self.diagnostic_hints
+            .iter()
+            .filter(|h| diagnostics.iter().any(|d| d.code.as_ref() == Some(&h.code)))
+            .collect()
+    }
+
     // Check that the exercise looks to be solved using self.state()
     // This is not the best way to check since
     // the user can just remove the "I AM NOT DONE" string from the file
@@ -282,6 +838,22 @@ path = "{}.rs""#,
     }
 }
 
+#[cfg(test)]
+impl Exercise {
+    // An exercise with no hints and the default build options
+    pub fn for_test(name: &str, path: &str, mode: Mode) -> Exercise {
+        Exercise {
+            name: name.into(),
+            path: PathBuf::from(path),
+            mode,
+            hint: String::new(),
+            diagnostic_hints: Vec::new(),
+            build: BuildOptions::default(),
+            expected: ExpectedOutput::default(),
+        }
+    }
+}
+
 impl Display for Exercise {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{}", self.path.to_str().unwrap())
@@ -289,8 +861,42 @@ impl Display for Exercise {
 }
 
 #[inline]
+// Run a command of an exercise, within the configured time
+fn execute(command: &mut Command, input: Option<&[u8]>) -> Result<Output, ExerciseOutput> {
+    let timeout = config::timeout();
+    match worker::output_with(command, input, timeout) {
+        Ok(cmd) => Ok(cmd),
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {
+            let seconds = timeout.unwrap_or_default().as_secs();
+            Err(ExerciseOutput {
+                stdout: String::new(),
+                stderr: tr!("run.timed_out", seconds),
+                diagnostics: Vec::new(),
+            })
+        }
+        Err(e) => panic!("Failed to run 'run' command: {e}"),
+    }
+}
+
+// A summary of the doc examples that failed, from the output of `rustdoc --test`
+// with lines like `test src/lib.rs - add (line 3) ... FAILED`
+fn failing_doc_examples(output: &str) -> String {
+    let re = Regex::new(r"(?m)^test .+ - (.+) \(line (\d+)\)(?: - .+)? \.\.\. FAILED$").unwrap();
+    let mut summary = String::new();
+    for example in re.captures_iter(output) {
+        summary.push_str(&format!("  {}\n", tr!("run.failing_doc_example", &example[2], &example[1])));
+    }
+    if summary.is_empty() {
+        return summary;
+    }
+    format!("{}\n{summary}", tr!("run.failing_doc_examples"))
+}
+
 fn clean() {
     let _ignored = remove_file(&temp_file());
+    let _ignored = remove_file(temp_lib());
+    let _ignored = remove_file(temp_bench());
+    let _ignored = remove_file(temp_bench_source());
 }
 
 #[cfg(test)]
@@ -301,12 +907,7 @@ mod test {
     #[test]
     fn test_clean() {
         File::create(&temp_file()).unwrap();
-        let exercise = Exercise {
-            name: String::from("example"),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
-        };
+        let exercise = Exercise::for_test("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
         let compiled = exercise.compile().unwrap();
         drop(compiled);
         assert!(!Path::new(&temp_file()).exists());
@@ -314,12 +915,7 @@ mod test {
 
     #[test]
     fn test_pending_state() {
-        let exercise = Exercise {
-            name: "pending_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("pending_exercise", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
 
         let state = exercise.state();
         let expected = vec![
@@ -355,25 +951,227 @@ mod test {
 
     #[test]
     fn test_finished_exercise() {
-        let exercise = Exercise {
-            name: "finished_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("finished_exercise", "tests/fixture/state/finished_exercise.rs", Mode::Compile);
 
         assert_eq!(exercise.state(), State::Done);
     }
 
     #[test]
     fn test_exercise_with_output() {
-        let exercise = Exercise {
-            name: "exercise_with_output".into(),
-            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("exercise_with_output", "tests/fixture/success/testSuccess.rs", Mode::Test);
         let out = exercise.compile().unwrap().run().unwrap();
         assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
     }
+
+    #[test]
+    fn test_compile_failure_diagnostics() {
+        let exercise = Exercise::for_test("testFailure", "tests/fixture/failure/testFailure.rs", Mode::Test);
+        let out = exercise.compile().err().unwrap();
+        let error = out.diagnostics.iter().find(|d| d.is_error()).unwrap();
+        assert_eq!(error.message, "cannot find macro `asset` in this scope");
+        let span = error.primary_span.as_ref().unwrap();
+        assert_eq!(span.file_name, "tests/fixture/failure/testFailure.rs");
+        assert_eq!(span.line_start, 3);
+    }
+
+    #[test]
+    fn test_diagnostic_hints_for() {
+        let mut exercise = Exercise::for_test(
+            "compMovedValue",
+            "tests/fixture/failure/compMovedValue.rs",
+            Mode::Compile,
+        );
+        exercise.diagnostic_hints = vec![
+            DiagnosticHint {
+                code: "E0382".into(),
+                hint: "Moved!".into(),
+                see: Some("move_semantics2".into()),
+            },
+            DiagnosticHint {
+                code: "E0499".into(),
+                hint: "Borrowed twice!".into(),
+                see: None,
+            },
+        ];
+        let out = exercise.compile().err().unwrap();
+        let hints = exercise.diagnostic_hints_for(&out.diagnostics);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].hint, "Moved!");
+    }
+
+    #[test]
+    fn test_validate_compile_fail() {
+        let list = |expected_errors: &str| {
+            toml::from_str::<ExerciseList>(&format!(
+                r#"
+[[exercises]]
+name = "borrow_twice"
+path = "borrow_twice.rs"
+mode = "compile_fail"
+hint = ""
+expected_errors = {expected_errors}
+"#
+            ))
+            .unwrap()
+        };
+        assert!(list(r#"["E0499"]"#).validate().is_ok());
+        assert!(list("[]").validate().unwrap_err().contains("borrow_twice"));
+    }
+
+    #[test]
+    fn test_validate_args() {
+        let list = |mode: &str| {
+            toml::from_str::<ExerciseList>(&format!(
+                r#"
+[[exercises]]
+name = "greet"
+path = "greet.rs"
+mode = "{mode}"
+hint = ""
+args = ["Ferris"]
+"#
+            ))
+            .unwrap()
+        };
+        assert!(list("compile").validate().is_ok());
+        assert!(list("doctest").validate().is_ok());
+        assert!(list("test").validate().unwrap_err().contains("greet"));
+    }
+
+    #[test]
+    fn test_validate_see() {
+        let list = |see: &str| {
+            toml::from_str::<ExerciseList>(&format!(
+                r#"
+[[exercises]]
+name = "move_semantics6"
+path = "move_semantics6.rs"
+mode = "compile"
+hint = ""
+
+[[exercises.diagnostic_hints]]
+code = "E0382"
+hint = "Moved!"
+see = "{see}"
+"#
+            ))
+            .unwrap()
+        };
+        assert!(list("move_semantics6").validate().is_ok());
+        assert!(list("move_semantics2")
+            .validate()
+            .unwrap_err()
+            .contains("move_semantics2"));
+        assert!(ExerciseList::from_file("info.toml").is_ok());
+    }
+
+    #[test]
+    fn test_build_script_runs_every_time() {
+        let runs = env::temp_dir().join(format!("rustlings_build_script_runs_{}", process::id()));
+        let _ = remove_file(&runs);
+        let mut exercise = Exercise::for_test(
+            "build_script_runs",
+            "tests/fixture/build_script/buildScriptRuns.rs",
+            Mode::BuildScript,
+        );
+        exercise
+            .build
+            .env
+            .insert("RUNS_FILE".into(), runs.display().to_string());
+        assert!(exercise.compile().is_ok());
+        assert!(exercise.compile().is_ok());
+        assert_eq!(fs::read_to_string(&runs).unwrap(), "run\nrun\n");
+        let _ = remove_file(&runs);
+    }
+
+    #[test]
+    fn test_min_rust_version() {
+        let mut exercise = Exercise::for_test(
+            "pending_exercise",
+            "tests/fixture/state/pending_exercise.rs",
+            Mode::Compile,
+        );
+        exercise.build.min_rust_version = Version::parse("99.0");
+        let out = exercise.compile().err().unwrap();
+        assert!(out.stderr.contains("99.0.0"));
+        assert!(out.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_run_fixtures() {
+        let build: BuildOptions = toml::from_str(
+            r#"
+args = ["a", "b c"]
+env = { NAME = "ferris" }
+stdin = "some input"
+"#,
+        )
+        .unwrap();
+        let mut exercise = Exercise::for_test(
+            "reads_input",
+            "tests/fixture/success/readsInput.rs",
+            Mode::Compile,
+        );
+        exercise.build = build;
+        let output = exercise.compile().unwrap().run().unwrap();
+        assert_eq!(output.stdout, "some input a,b c ferris\n");
+
+        exercise.build.stdin = Some(Stdin::File {
+            file: PathBuf::from("tests/fixture/missing.txt"),
+        });
+        let error = exercise.compile().unwrap().run().unwrap_err();
+        assert!(error.stderr.contains("tests/fixture/missing.txt"));
+    }
+
+    #[test]
+    fn test_compile_fail() {
+        let exercise = |path: &str, code: &str| {
+            let mut exercise = Exercise::for_test("compile_fail", path, Mode::CompileFail);
+            exercise.expected.expected_errors = vec![code.to_string()];
+            exercise
+        };
+        let moved = "tests/fixture/failure/compMovedValue.rs";
+        assert!(exercise(moved, "E0382").compile().is_ok());
+        let output = exercise(moved, "E0499").compile().err().unwrap();
+        assert!(output.stdout.contains("E0499"));
+        assert_eq!(output.diagnostics[0].code.as_deref(), Some("E0382"));
+        let output = exercise("tests/fixture/success/compSuccess.rs", "E0382")
+            .compile()
+            .err()
+            .unwrap();
+        assert!(output.stderr.contains("E0382"));
+    }
+
+    #[test]
+    fn test_doctest() {
+        let exercise = Exercise::for_test("doc-failure", "tests/fixture/failure/docFailure.rs", Mode::Doctest);
+        let output = exercise.compile().unwrap().run().unwrap_err();
+        assert!(output.stdout.contains("1 passed; 1 failed"));
+        assert!(output.stderr.contains("line 8: two"));
+        assert!(!output.stderr.contains("add_one"));
+    }
+
+    #[test]
+    fn test_build_options() {
+        let build: BuildOptions = toml::from_str(
+            r#"
+edition = "2018"
+cfg = ['feature="extra"']
+rustc_args = ["-Copt-level=1"]
+clippy_lints = { "clippy::needless_return" = "warn" }
+"#,
+        )
+        .unwrap();
+        let mut exercise = Exercise::for_test("build_options", "build_options.rs", Mode::Clippy);
+        exercise.build = build;
+        assert_eq!(
+            exercise.rustc_flags(),
+            ["--edition", "2018", "--cfg", "feature=\"extra\"", "-Copt-level=1"]
+        );
+        assert_eq!(
+            exercise.clippy_lint_flags(),
+            ["-D", "warnings", "-W", "clippy::needless_return"]
+        );
+        assert!(toml::from_str::<BuildOptions>("edition = \"2020\"").is_err());
+    }
 }
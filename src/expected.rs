@@ -0,0 +1,127 @@
+use console::style;
+use regex::Regex;
+use serde::Deserialize;
+
+// What an `output` exercise must print, or which errors a `compile_fail`
+// exercise must fail with, as set in info.toml
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ExpectedOutput {
+    // The expected output. Without it, the output is read from the `.expected`
+    // file next to the exercise.
+    #[serde(default)]
+    pub expected_output: Option<String>,
+    // How the output is compared to the expected one
+    #[serde(default)]
+    pub output_match: OutputMatch,
+    // The error codes compiling the exercise must report, like `E0499`.
+    // `compile_fail` exercises must list at least one.
+    #[serde(default)]
+    pub expected_errors: Vec<String>,
+}
+
+// How the output of an `output` exercise is compared to the expected one
+#[derive(Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMatch {
+    // Byte for byte
+    Exact,
+    // Ignoring whitespace at the end of lines, and blank lines around the output
+    #[default]
+    Trimmed,
+    // The expected output is a regular expression the whole output must match
+    Regex,
+}
+
+// Compare the output of an exercise to the expected one. A mismatch is
+// explained with a colored diff, or with the pattern the output doesn't match.
+pub fn check(expected: &str, actual: &str, matching: OutputMatch) -> Result<(), String> {
+    let matches = match matching {
+        OutputMatch::Exact => expected == actual,
+        OutputMatch::Trimmed => trim(expected) == trim(actual),
+        OutputMatch::Regex => {
+            // The output usually ends with the newline of `println!`
+            let regex = Regex::new(&format!(r"\A(?:{expected})\n?\z"))
+                .map_err(|e| tr!("run.invalid_expected_output", e))?;
+            regex.is_match(actual)
+        }
+    };
+    if matches {
+        return Ok(());
+    }
+    let explanation = match matching {
+        OutputMatch::Exact => diff(expected, actual),
+        OutputMatch::Trimmed => diff(&trim(expected), &trim(actual)),
+        OutputMatch::Regex => format!(
+            "{}\n{}\n\n{}\n{actual}",
+            style(tr!("run.expected_pattern")).bold(),
+            style(expected).red(),
+            style(tr!("run.actual_output")).bold(),
+        ),
+    };
+    Err(format!("{}\n{explanation}", tr!("run.output_mismatch")))
+}
+
+fn trim(output: &str) -> String {
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+// A line diff of the expected and the actual output: the lines only expected
+// are marked with `-`, the ones only printed with `+`
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.split('\n').collect();
+    let actual: Vec<&str> = actual.split('\n').collect();
+
+    // The length of the longest common subsequence of the remaining lines
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            out.push_str(&format!("  {}\n", style(expected[i]).dim()));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1]) {
+            out.push_str(&format!("{}\n", style(format!("- {}", expected[i])).red()));
+            i += 1;
+        } else {
+            out.push_str(&format!("{}\n", style(format!("+ {}", actual[j])).green()));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matching() {
+        assert!(check("a\nb\n", "a\nb\n", OutputMatch::Exact).is_ok());
+        assert!(check("a\nb", "a\nb\n", OutputMatch::Exact).is_err());
+        assert!(check("a\nb", "\na  \nb\n\n", OutputMatch::Trimmed).is_ok());
+        assert!(check("a\nc", "a\nb", OutputMatch::Trimmed).is_err());
+        assert!(check(r"x = \d+", "x = 42\n", OutputMatch::Regex).is_ok());
+        assert!(check(r"x = \d+", "x = 42!\n", OutputMatch::Regex).is_err());
+        assert!(check(r"x = \d+", "x = 42\n\n", OutputMatch::Regex).is_err());
+        assert!(check(r"x = \d+\ny = \d+", "x = 1\ny = 2\n", OutputMatch::Regex).is_ok());
+        assert!(check("(", "(", OutputMatch::Regex).is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = console::strip_ansi_codes(&diff("a\nb\nc", "a\nx\nc")).to_string();
+        assert_eq!(diff, "  a\n- b\n+ x\n  c\n");
+    }
+}
@@ -0,0 +1,734 @@
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// Look up a message in the catalog for the language in use.
+// With arguments, they replace the `{0}`, `{1}`, ... placeholders.
+macro_rules! tr {
+    ($key:literal) => {
+        $crate::i18n::text($key)
+    };
+    ($key:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format($key, &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+
+// The languages rustlings' messages are available in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    ZhCn,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::En, Lang::ZhCn];
+
+    // Parse a language tag like `zh-CN`, or a locale like `zh_CN.UTF-8`
+    pub fn parse(tag: &str) -> Option<Lang> {
+        let tag = tag
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase();
+        match tag.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "zh" | "zh-cn" | "zh-sg" | "zh-hans" | "zh-hans-cn" => Some(Lang::ZhCn),
+            tag if tag.starts_with("en-") => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    // The tag the language is known by, also in the hint tables of info.toml
+    pub fn tag(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::ZhCn => "zh-CN",
+        }
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+// Pick the language of the messages from `--lang`, or else from the
+// LC_ALL, LC_MESSAGES and LANG environment variables, falling back to English
+pub fn init(flag: Option<&str>) -> Result<(), String> {
+    let lang = match flag {
+        Some(tag) => Lang::parse(tag).ok_or_else(|| {
+            let tags: Vec<&str> = Lang::ALL.iter().map(|lang| lang.tag()).collect();
+            tr!("main.unknown_lang", tag, tags.join(", "))
+        })?,
+        None => locale(),
+    };
+    let _ = LANG.set(lang);
+    Ok(())
+}
+
+// The language of the LC_ALL, LC_MESSAGES and LANG environment variables
+fn locale() -> Lang {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| Lang::parse(&locale))
+        .unwrap_or(Lang::En)
+}
+
+// Before `init`, like while reading the configuration, go by the locale
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or_else(locale)
+}
+
+// Deserialize text from info.toml that is either a plain string, or a table
+// of translations keyed by language tag, like
+//
+//     [exercises.hint]
+//     en = "..."
+//     zh-CN = "..."
+//
+// keeping the translation for the language in use, or else the English one
+pub fn localized<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Text {
+        Plain(String),
+        Translations(BTreeMap<String, String>),
+    }
+
+    match Text::deserialize(deserializer)? {
+        Text::Plain(text) => Ok(text),
+        Text::Translations(translations) => pick(lang(), translations)
+            .ok_or_else(|| serde::de::Error::custom("expected at least one translation")),
+    }
+}
+
+fn pick(lang: Lang, mut translations: BTreeMap<String, String>) -> Option<String> {
+    let tag = |lang: Lang| {
+        translations
+            .keys()
+            .find(|tag| Lang::parse(tag) == Some(lang))
+            .cloned()
+    };
+    let key = tag(lang)
+        .or_else(|| tag(Lang::En))
+        .or_else(|| translations.keys().next().cloned())?;
+    translations.remove(&key)
+}
+
+pub fn text(key: &'static str) -> &'static str {
+    match MESSAGES.iter().find(|(k, _, _)| *k == key) {
+        Some((_, en, zh_cn)) => match lang() {
+            Lang::En => en,
+            Lang::ZhCn => zh_cn,
+        },
+        // A missing message is a bug, show its key rather than nothing
+        None => key,
+    }
+}
+
+pub fn format(key: &'static str, args: &[&dyn Display]) -> String {
+    substitute(text(key), args)
+}
+
+fn substitute(template: &str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..]
+            .find('}')
+            .and_then(|end| Some((rest[start + 1..start + 1 + end].parse::<usize>().ok()?, end)));
+        match placeholder {
+            Some((index, end)) if index < args.len() => {
+                result.push_str(&args[index].to_string());
+                rest = &rest[start + end + 2..];
+            }
+            _ => {
+                result.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+// The message catalog: a key, the English text and the Simplified Chinese text
+const MESSAGES: &[(&str, &str, &str)] = &[
+    (
+        "main.wrong_directory",
+        "{0} must be run from the rustlings directory\nTry `cd rustlings/`!",
+        "{0} 必须在 rustlings 目录下运行\n试试 `cd rustlings/`！",
+    ),
+    (
+        "main.no_toolchain",
+        "The toolchain '{0}' doesn't work. Is it installed? Try `rustup toolchain install {0}`.",
+        "工具链 '{0}' 无法使用。是否已经安装？请尝试 `rustup toolchain install {0}`。",
+    ),
+    (
+        "main.no_rustc",
+        "We cannot find `rustc`.\nTry running `rustc --version` to diagnose your problem.\nFor instructions on how to install Rust, check the README.",
+        "找不到 `rustc`。\n可以运行 `rustc --version` 来诊断问题。\n安装 Rust 的方法请参阅 README。",
+    ),
+    (
+        "main.no_exercise",
+        "No exercise found for '{0}'!",
+        "找不到练习 '{0}'！",
+    ),
+    (
+        "main.all_done",
+        "🎉 Congratulations! You have done all the exercises!\n🔚 There are no more exercises to do next!",
+        "🎉 恭喜！你已经完成了所有练习！\n🔚 没有下一个练习了！",
+    ),
+    (
+        "main.progress",
+        "Progress: You completed {0} / {1} exercises ({2} %).",
+        "进度：你已完成 {0} / {1} 个练习（{2} %）。",
+    ),
+    (
+        "main.watch_finished",
+        "{0} All exercises completed! {0}",
+        "{0} 所有练习都完成了！{0}",
+    ),
+    (
+        "main.watch_unfinished",
+        "We hope you're enjoying learning about Rust!\nIf you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again",
+        "希望你喜欢学习 Rust！\n如果想以后继续做练习，再次运行 `rustlings watch` 即可",
+    ),
+    (
+        "main.unknown_color",
+        "Unknown color choice '{0}', use one of: auto, always, never",
+        "未知的颜色选项 '{0}'，请使用以下之一：auto、always、never",
+    ),
+    (
+        "main.unknown_reporter",
+        "Unknown reporter '{0}', use one of: fancy, plain, ndjson",
+        "未知的报告方式 '{0}'，请使用以下之一：fancy、plain、ndjson",
+    ),
+    ("main.info_toml_unparsable", "Failed to parse {0}: {1}", "解析 {0} 失败：{1}"),
+    (
+        "main.no_sysroot",
+        "Failed to find the Rust standard library sources for rust-analyzer:",
+        "找不到 rust-analyzer 需要的 Rust 标准库源码：",
+    ),
+    (
+        "main.no_exercises_found",
+        "Failed to find any exercises, make sure you're in the `rustlings` folder",
+        "找不到任何练习，请确认你在 `rustlings` 目录中",
+    ),
+    (
+        "main.rust_project_unwritable",
+        "Failed to write rust-project.json to disk for rust-analyzer",
+        "无法为 rust-analyzer 写入 rust-project.json",
+    ),
+    (
+        "main.rust_project_written",
+        "Successfully generated rust-project.json\nrust-analyzer will now parse exercises, restart your language server or editor",
+        "已生成 rust-project.json\nrust-analyzer 现在会解析练习，请重启你的语言服务器或编辑器",
+    ),
+    (
+        "main.workspace_generated",
+        "Successfully generated {0}\nEvery exercise is a package named after it. Try:\n  cargo check --manifest-path {0}\n  cargo test --manifest-path {0} -p iterators1\nRun `rustlings workspace` again when info.toml changes.",
+        "已生成 {0}\n每个练习都是一个以练习命名的包。试试：\n  cargo check --manifest-path {0}\n  cargo test --manifest-path {0} -p iterators1\ninfo.toml 改变后请再次运行 `rustlings workspace`。",
+    ),
+    ("main.workspace_failed", "Failed to generate the workspace: {0}", "生成工作区失败：{0}"),
+    (
+        "main.watch_failed",
+        "Error: Could not watch your progress. Error message was {0}.\nMost likely you've run out of disk space or the exercises directory is not readable.",
+        "错误：无法监视你的进度。错误信息为 {0}。\n很可能是磁盘空间不足，或者 exercises 目录不可读。",
+    ),
+    ("main.config_unset", "`{0}` is not set", "`{0}` 未设置"),
+    ("main.config_set", "Set `{0}` in {1}", "已在 {1} 中设置 `{0}`"),
+    (
+        "main.default_out",
+        r#"Thanks for installing Rustlings!
+
+Is this your first time? Don't worry, Rustlings was made for beginners! We are
+going to teach you a lot of things about Rust, but before we can get
+started, here's a couple of notes about how Rustlings operates:
+
+1. The central concept behind Rustlings is that you solve exercises. These
+   exercises usually have some sort of syntax error in them, which will cause
+   them to fail compilation or testing. Sometimes there's a logic error instead
+   of a syntax error. No matter what error, it's your job to find it and fix it!
+   You'll know when you fixed it because then, the exercise will compile and
+   Rustlings will be able to move on to the next exercise.
+2. If you run Rustlings in watch mode (which we recommend), it'll automatically
+   start with the first exercise. Don't get confused by an error message popping
+   up as soon as you run Rustlings! This is part of the exercise that you're
+   supposed to solve, so open the exercise file in an editor and start your
+   detective work!
+3. If you're stuck on an exercise, there is a helpful hint you can view by typing
+   'hint' (in watch mode), or running `rustlings hint exercise_name`.
+4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
+   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
+   and sometimes, other learners do too so you can help each other out!
+5. If you want to use `rust-analyzer` with exercises, which provides features like
+   autocompletion, run the command `rustlings lsp`.
+
+Got all that? Great! To get started, run `rustlings watch` in order to get the first
+exercise. Make sure to have your editor open!"#,
+        r#"感谢安装 Rustlings！
+
+第一次使用？别担心，Rustlings 就是为初学者准备的！我们会教你很多关于 Rust
+的知识，不过在开始之前，先了解一下 Rustlings 是怎么运作的：
+
+1. Rustlings 的核心就是做练习。这些练习里通常有某种语法错误，会导致编译或测试
+   失败。有时则是逻辑错误而不是语法错误。不管是什么错误，你的任务就是找到并修复
+   它！修好之后练习就能编译通过，Rustlings 也就能进入下一个练习。
+2. 如果你在 watch 模式下运行 Rustlings（我们推荐这样做），它会自动从第一个练习
+   开始。运行后马上出现错误信息也不要困惑！这正是你要解决的练习，用编辑器打开
+   练习文件，开始你的侦探工作吧！
+3. 如果卡在某个练习上，可以查看提示：在 watch 模式下输入 'hint'，或者运行
+   `rustlings hint 练习名称`。
+4. 如果觉得某个练习讲不通，欢迎在 GitHub 上提 issue！
+   (https://github.com/rust-lang/rustlings/issues/new)。我们会看每一个 issue，
+   其他学习者有时也会看，大家可以互相帮助！
+5. 如果想在练习中使用 `rust-analyzer` 获得自动补全等功能，请运行
+   `rustlings lsp`。
+
+都明白了吗？很好！运行 `rustlings watch` 开始第一个练习吧。记得打开你的编辑器！"#,
+    ),
+    (
+        "main.unknown_lang",
+        "Unsupported language '{0}', use one of: {1}",
+        "不支持的语言 '{0}'，请使用以下之一：{1}",
+    ),
+    ("list.name", "Name", "名称"),
+    ("list.path", "Path", "路径"),
+    ("list.status", "Status", "状态"),
+    ("list.done", "Done", "已完成"),
+    ("list.pending", "Pending", "待完成"),
+    ("list.skipped", "Skipped", "已跳过"),
+    ("config.unreadable", "Failed to read {0}: {1}", "读取 {0} 失败：{1}"),
+    ("config.unparsable", "Failed to parse {0}: {1}", "解析 {0} 失败：{1}"),
+    ("config.invalid", "Invalid setting in {0}: {1}", "{0} 中的设置无效：{1}"),
+    ("config.no_home", "Failed to find your home directory", "找不到你的主目录"),
+    ("config.unwritable", "Failed to write {0}: {1}", "写入 {0} 失败：{1}"),
+    ("config.unsupported_lang", "unsupported language '{0}'", "不支持的语言 '{0}'"),
+    ("config.at_least_one", "`jobs` and `timeout` must be at least 1", "`jobs` 和 `timeout` 至少为 1"),
+    ("config.invalid_value", "'{0}' is not a valid value for `{1}`", "'{0}' 不是 `{1}` 的有效值"),
+    ("config.unknown_key", "Unknown setting `{0}`, use one of: {1}", "未知的设置 `{0}`，请使用以下之一：{1}"),
+    ("project.toolchain", "Determined toolchain: {0}\n", "已确定工具链：{0}\n"),
+    (
+        "project.build_script_failed",
+        "Couldn't run {0} ({1}), run `rustlings lsp` again once it works to get its cfgs and environment variables in your editor.",
+        "无法运行 {0}（{1}），等它能运行后再次运行 `rustlings lsp`，编辑器才能获得它的 cfg 和环境变量。",
+    ),
+    ("project.build_script_uncompilable", "it doesn't compile yet", "它还无法编译"),
+    ("project.build_script_crashed", "it failed to run", "它运行失败了"),
+    (
+        "project.sysroot_failed",
+        "`rustc --print sysroot` failed: {0}",
+        "`rustc --print sysroot` 失败：{0}",
+    ),
+    (
+        "project.bad_rust_src_path",
+        "RUST_SRC_PATH is set to {0}, which doesn't contain the standard library sources",
+        "RUST_SRC_PATH 被设置为 {0}，但其中没有标准库源码",
+    ),
+    (
+        "project.no_rust_src_component",
+        "The `rust-src` component isn't installed for the {0} toolchain. Run `rustup component add rust-src --toolchain {0}` and try again.",
+        "{0} 工具链没有安装 `rust-src` 组件。请运行 `rustup component add rust-src --toolchain {0}` 后重试。",
+    ),
+    (
+        "project.no_rust_src",
+        "The standard library sources aren't installed in {0}. Install them with your Rust distribution, or set RUST_SRC_PATH to their `library` directory.",
+        "{0} 中没有安装标准库源码。请通过你的 Rust 发行版安装，或将 RUST_SRC_PATH 设置为它们的 `library` 目录。",
+    ),
+    (
+        "exercise.no_workload",
+        "The bench exercise {0} has no workload, set one in `[exercises.bench]`",
+        "性能练习 {0} 没有工作负载，请在 `[exercises.bench]` 中设置",
+    ),
+    (
+        "exercise.no_expected_errors",
+        "The compile_fail exercise {0} has no expected errors, list them in `expected_errors`",
+        "compile_fail 练习 {0} 没有预期的错误，请在 `expected_errors` 中列出",
+    ),
+    (
+        "exercise.args_for_tests",
+        "The exercise {0} runs as tests and can't take `args`",
+        "练习 {0} 以测试方式运行，不能使用 `args`",
+    ),
+    (
+        "exercise.unknown_see",
+        "A diagnostic hint of {0} refers to {1}, which is not an exercise",
+        "{0} 的诊断提示引用了 {1}，但它不是一个练习",
+    ),
+    ("diagnostics.error", "{0} error", "{0} 个错误"),
+    ("diagnostics.errors", "{0} errors", "{0} 个错误"),
+    ("diagnostics.lint", "{0} lint", "{0} 个 lint"),
+    ("diagnostics.lints", "{0} lints", "{0} 个 lint"),
+    ("diagnostics.warning", "{0} warning", "{0} 个警告"),
+    ("diagnostics.warnings", "{0} warnings", "{0} 个警告"),
+    (
+        "diagnostics.warnings_hidden",
+        " (warnings hidden until the errors are fixed)",
+        "（修复错误之前不显示警告）",
+    ),
+    ("reporter.progress", "Progress:", "进度："),
+    (
+        "bench.timings",
+        "Timings of the workload by size:",
+        "工作负载在各规模下的耗时：",
+    ),
+    (
+        "bench.over_budget",
+        "The workload took {1} at size {0}, over the budget of {2}.",
+        "工作负载在规模 {0} 下耗时 {1}，超出了 {2} 的预算。",
+    ),
+    (
+        "bench.stopped",
+        "The workload was stopped, as some run took longer than the budget of {0}.",
+        "工作负载已被终止，因为有一次运行超出了 {0} 的预算。",
+    ),
+    (
+        "bench.too_slow",
+        "The running time grows faster than O({0}): it grows like n^{1}.",
+        "运行时间的增长快于 O({0})：实际增长约为 n^{1}。",
+    ),
+    (
+        "run.output_mismatch",
+        "The output doesn't match the expected one:",
+        "输出与预期不符：",
+    ),
+    ("run.expected_pattern", "Expected output matching:", "预期输出应匹配："),
+    ("run.actual_output", "Actual output:", "实际输出："),
+    (
+        "run.invalid_expected_output",
+        "The expected output is not a valid regular expression: {0}",
+        "预期输出不是有效的正则表达式：{0}",
+    ),
+    (
+        "run.no_expected_output",
+        "{0} has no expected output. Set `expected_output` in info.toml, or create {1}.",
+        "{0} 没有预期输出。请在 info.toml 中设置 `expected_output`，或创建 {1}。",
+    ),
+    ("run.failing_doc_examples", "Failing doc examples:", "失败的文档示例："),
+    ("run.failing_doc_example", "line {0}: {1}", "第 {0} 行：{1}"),
+    (
+        "run.stdin_unreadable",
+        "Failed to read the input of the exercise from {0}: {1}",
+        "无法从 {0} 读取练习的输入：{1}",
+    ),
+    (
+        "run.timed_out",
+        "The exercise was stopped after running for {0} seconds. Is there an endless loop?",
+        "练习运行超过 {0} 秒，已被停止。是否存在死循环？",
+    ),
+    ("verify.compiling", "Compiling {0}...", "正在编译 {0}..."),
+    (
+        "verify.rust_too_old",
+        "{0} needs Rust {1} or newer, but the rustc in use is {2}.\nUpdate it with `rustup update`, or pick a newer toolchain with `--toolchain`.",
+        "{0} 需要 Rust {1} 或更新的版本，但当前使用的 rustc 是 {2}。\n请用 `rustup update` 更新，或用 `--toolchain` 选择更新的工具链。",
+    ),
+    ("verify.running", "Running {0}...", "正在运行 {0}..."),
+    ("verify.testing", "Testing {0}...", "正在测试 {0}..."),
+    ("verify.ran_with_errors", "Ran {0} with errors", "运行 {0} 时出错"),
+    (
+        "verify.testing_failed",
+        "Testing of {0} failed! Please try again. Here's the output:",
+        "{0} 的测试失败了！请再试一次。输出如下：",
+    ),
+    (
+        "verify.compiling_failed",
+        "Compiling of {0} failed! Please try again. Here's the output:",
+        "{0} 编译失败！请再试一次。输出如下：",
+    ),
+    ("verify.hint_for", "Hint for {0}:", "关于 {0} 的提示："),
+    (
+        "verify.covered_in",
+        "This is covered in {0}, have another look with `rustlings run {1}`.",
+        "这在 {0} 中讲过，可以用 `rustlings run {1}` 再看一遍。",
+    ),
+    ("verify.ran", "Successfully ran {0}!", "成功运行 {0}！"),
+    ("verify.tested", "Successfully tested {0}!", "成功测试 {0}！"),
+    ("verify.compiled", "Successfully compiled {0}!", "成功编译 {0}！"),
+    ("verify.compiles", "The code is compiling!", "代码编译通过了！"),
+    (
+        "verify.doctests_pass",
+        "The code is compiling, and the doc examples pass!",
+        "代码编译通过，文档示例也都通过了！",
+    ),
+    (
+        "verify.fast_enough",
+        "The code is compiling, the tests pass, and it's fast enough!",
+        "代码编译通过，测试通过，而且足够快！",
+    ),
+    (
+        "verify.fails_to_compile",
+        "The code fails to compile, just as it should!",
+        "代码如预期般无法通过编译！",
+    ),
+    (
+        "verify.failed_as_expected",
+        "{0} fails to compile as expected!",
+        "{0} 如预期般编译失败！",
+    ),
+    (
+        "verify.compile_fail_failed",
+        "{0} doesn't fail to compile as expected! Please try again. Here's the output:",
+        "{0} 没有如预期般编译失败！请再试一次。输出如下：",
+    ),
+    (
+        "verify.compiled_without_errors",
+        "{0} compiles, but it should fail to compile with {1}.",
+        "{0} 通过了编译，但它应该以 {1} 编译失败。",
+    ),
+    (
+        "verify.missing_errors",
+        "{0} should fail to compile with {1}, but the compiler reports other errors.",
+        "{0} 应该以 {1} 编译失败，但编译器报告了其他错误。",
+    ),
+    (
+        "verify.output_matches",
+        "The code is compiling, and the output is as expected!",
+        "代码编译通过，输出也符合预期！",
+    ),
+    (
+        "verify.tests_pass",
+        "The code is compiling, and the tests pass!",
+        "代码编译通过，测试也通过了！",
+    ),
+    (
+        "verify.clippy_happy",
+        "The code is compiling, and {0}Clippy{1} is happy!",
+        "代码编译通过，{0}Clippy{1} 也很满意！",
+    ),
+    ("verify.build_script_works", "Build script works!", "构建脚本可以工作了！"),
+    ("verify.output", "Output:", "输出："),
+    ("verify.hints", "Hints:", "提示："),
+    (
+        "verify.keep_working",
+        "You can keep working on this exercise,\nor jump into the next one by removing the {0} comment:",
+        "你可以继续完善这个练习，\n或者删除 {0} 注释进入下一个练习：",
+    ),
+    (
+        "watch.welcome",
+        "Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.",
+        "欢迎进入 watch 模式！输入 'help' 可以查看这里能用的命令。",
+    ),
+    (
+        "watch.zero_poll_interval",
+        "The polling interval must be at least 1 millisecond.",
+        "轮询间隔至少为 1 毫秒。",
+    ),
+    (
+        "watch.help",
+        r#"Commands available to you in watch mode:
+  hint         - prints the current exercise's hint
+  list         - lists all exercises and their status
+  next         - runs the next unsolved exercise
+  run <name>   - runs a single exercise
+  reset [name] - resets the current or the given exercise
+  edit [name]  - opens the current or the given exercise in your editor
+  skip         - skips the current exercise until watch mode is restarted
+  verify all   - verifies all exercises, including the solved ones
+  explain      - explains the last compiler error, like `rustc --explain`
+  clear        - clears the screen
+  quit         - quits watch mode
+  !<cmd>       - executes a command, like `!rustc --explain E0381`
+  help         - displays this help message
+
+Watch mode automatically re-evaluates the current exercise
+when you edit a file's contents. With `--only`, 'next' and 'skip'
+move on to the next exercise of the topic."#,
+        r#"watch 模式下可用的命令：
+  hint         - 显示当前练习的提示
+  list         - 列出所有练习及其状态
+  next         - 运行下一个未完成的练习
+  run <name>   - 运行指定的练习
+  reset [name] - 重置当前练习或指定的练习
+  edit [name]  - 用编辑器打开当前练习或指定的练习
+  skip         - 跳过当前练习，直到重新启动 watch 模式
+  verify all   - 验证所有练习，包括已完成的
+  explain      - 解释最近一次的编译错误，类似 `rustc --explain`
+  clear        - 清屏
+  quit         - 退出 watch 模式
+  !<cmd>       - 执行一条命令，例如 `!rustc --explain E0381`
+  help         - 显示这条帮助信息
+
+编辑文件内容后，watch 模式会自动重新检查当前练习。使用 `--only` 时，
+'next' 和 'skip' 会进入该主题的下一个练习。"#,
+    ),
+    ("watch.no_notifications", "Could not watch for file changes: {0}", "无法监视文件变化：{0}"),
+    (
+        "watch.polling_fallback",
+        "Most likely your 'inotify limit' has been reached. Falling back to polling for changes,\nuse `rustlings watch --poll` to go straight to polling next time.",
+        "很可能是达到了 'inotify limit' 上限。改为轮询检查文件变化，\n下次可以使用 `rustlings watch --poll` 直接轮询。",
+    ),
+    ("watch.error", "watch error: {0}", "监视出错：{0}"),
+    ("watch.no_focus", "No exercise or topic found for '{0}'!", "找不到练习或主题 '{0}'！"),
+    (
+        "watch.no_editor",
+        "No editor is configured, set one with `rustlings config set editor <command>`.",
+        "没有配置编辑器，请用 `rustlings config set editor <command>` 设置一个。",
+    ),
+    ("watch.empty_editor", "The configured editor command is empty.", "配置的编辑器命令为空。"),
+    ("watch.editor_failed", "Failed to start `{0}`: {1}", "无法启动 `{0}`：{1}"),
+    ("watch.missing_quote", "missing closing `{0}`", "缺少闭合的 `{0}`"),
+    ("watch.bye", "Bye!", "再见！"),
+    ("watch.no_command", "no command provided", "没有提供命令"),
+    ("watch.command_failed", "failed to execute command `{0}`: {1}", "无法执行命令 `{0}`：{1}"),
+    ("watch.command_unparsable", "failed to parse command `{0}`: {1}", "无法解析命令 `{0}`：{1}"),
+    ("watch.run_usage", "usage: run <name>", "用法：run <name>"),
+    ("watch.unknown_command", "unknown command: {0}", "未知命令：{0}"),
+    ("watch.read_error", "error reading command: {0}", "读取命令出错：{0}"),
+    ("watch.last_of_focus", "That was the last exercise of {0}!", "这是 {0} 的最后一个练习！"),
+    (
+        "watch.skipped_done",
+        "All exercises but the {0} you skipped are done!",
+        "除了你跳过的 {0} 个练习，其他练习都完成了！",
+    ),
+    (
+        "watch.restart_for_skipped",
+        "Restart watch mode to get back to them.",
+        "重新启动 watch 模式即可回到这些练习。",
+    ),
+    ("watch.exercise_done", "{0} is done!", "{0} 完成了！"),
+    ("watch.type_next", "Type 'next' to move on to {0}.", "输入 'next' 进入 {0}。"),
+    ("watch.focus_done", "That's the last exercise of {0}, well done!", "这是 {0} 的最后一个练习，干得好！"),
+    ("watch.reloaded", "Reloaded {0}", "已重新加载 {0}"),
+    ("watch.reload_failed", "Failed to reload info.toml: {0}", "重新加载 info.toml 失败：{0}"),
+    (
+        "watch.keeps_previous_list",
+        "Watch mode keeps using the previous exercise list until the error is fixed.",
+        "在错误修复之前，watch 模式会继续使用之前的练习列表。",
+    ),
+    (
+        "watch.current_removed",
+        "The file of your current exercise {0} was removed!",
+        "你当前练习 {0} 的文件被删除了！",
+    ),
+    ("watch.exercise_removed", "The exercise file {0} was removed!", "练习文件 {0} 被删除了！"),
+    (
+        "watch.restore_with",
+        "You can restore it with `rustlings reset {0}`.",
+        "可以用 `rustlings reset {0}` 恢复它。",
+    ),
+    ("watch.nothing_next", "There are no more exercises to do next!", "没有下一个练习了！"),
+    ("watch.reset_failed", "Failed to reset {0}", "重置 {0} 失败"),
+    ("watch.nothing_to_reset", "There is no current exercise to reset.", "没有可以重置的当前练习。"),
+    ("watch.nothing_to_skip", "There is no current exercise to skip.", "没有可以跳过的当前练习。"),
+    ("watch.nothing_to_edit", "There is no current exercise to edit.", "没有可以编辑的当前练习。"),
+    (
+        "watch.explain_failed",
+        "failed to execute `rustc --explain {0}`: {1}",
+        "无法执行 `rustc --explain {0}`：{1}",
+    ),
+    (
+        "watch.no_error_code",
+        "No compiler error with an error code was seen yet.",
+        "还没有出现带错误代码的编译错误。",
+    ),
+    (
+        "tui.key_bindings",
+        "[h]int  [n]ext  [r]erun  [e]dit  [:]command  [q]uit",
+        "[h]提示  [n]下一个  [r]重新检查  [e]编辑  [:]命令  [q]退出",
+    ),
+    ("tui.exercise", "Exercise", "练习"),
+    ("tui.hint", "Hint", "提示"),
+    ("tui.output", "Output", "输出"),
+    ("tui.more_lines", "... {0} more lines", "... 还有 {0} 行"),
+    ("tui.checking", "Checking...", "检查中..."),
+    ("tui.check_failed", "Checking the exercises failed.", "检查练习失败。"),
+    ("tui.done", "✓ {0} is done!", "✓ {0} 完成了！"),
+    ("tui.press_next", "Press n to move on.", "按 n 继续。"),
+    ("tui.works", "✓ The exercise works!", "✓ 练习可以运行了！"),
+    (
+        "tui.remove_comment",
+        "Remove the `I AM NOT DONE` comment to move on.",
+        "删除 `I AM NOT DONE` 注释以继续。",
+    ),
+    ("tui.failed", "✗ The exercise doesn't work yet.", "✗ 练习还不能运行。"),
+    ("tui.reloaded", "Reloaded {0}, checking...", "已重新加载 {0}，检查中..."),
+    (
+        "tui.reload_failed",
+        "Failed to reload info.toml, keeping the previous exercise list:",
+        "重新加载 info.toml 失败，继续使用之前的练习列表：",
+    ),
+    ("tui.all_done", "All exercises are done! Press any key to exit.", "所有练习都完成了！按任意键退出。"),
+    ("cicv.passed", "{0} passed", "{0} 执行成功"),
+    ("cicv.failed", "{0} failed", "{0} 执行失败"),
+    ("cicv.total", "Total exercises: {0}", "总的题目数: {0}"),
+    ("cicv.passed_so_far", "Exercises passed so far: {0}", "当前做正确的题目数: {0}"),
+    ("cicv.elapsed", "Time spent on this exercise: {0} s", "当前修改试卷耗时: {0} s"),
+    (
+        "cicv.finished",
+        "=============================== Grading finished, total time: {0} s ==================================",
+        "===============================试卷批改完成,总耗时: {0} s; ==================================",
+    ),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_parse_lang() {
+        assert_eq!(Lang::parse("zh_CN.UTF-8"), Some(Lang::ZhCn));
+        assert_eq!(Lang::parse("zh-CN"), Some(Lang::ZhCn));
+        assert_eq!(Lang::parse("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("C"), Some(Lang::En));
+        assert_eq!(Lang::parse("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_substitute() {
+        assert_eq!(substitute("{0} of {1}", &[&1, &"2"]), "1 of 2");
+        assert_eq!(substitute("{not a placeholder} {0}", &[&1]), "{not a placeholder} 1");
+    }
+
+    #[test]
+    fn test_catalog_placeholders_match() {
+        let placeholders = |text: &str| -> Vec<String> {
+            let mut found: Vec<String> = (0..4)
+                .map(|i| format!("{{{i}}}"))
+                .filter(|placeholder| text.contains(placeholder.as_str()))
+                .collect();
+            found.sort();
+            found
+        };
+        for (key, en, zh_cn) in MESSAGES {
+            assert_eq!(placeholders(en), placeholders(zh_cn), "{key}");
+        }
+    }
+
+    #[test]
+    fn test_catalog_complete() {
+        let mut keys = BTreeSet::new();
+        for (key, en, zh_cn) in MESSAGES {
+            assert!(keys.insert(*key), "{key} is in the catalog twice");
+            assert!(!en.is_empty(), "{key} has no English text");
+            assert!(!zh_cn.is_empty(), "{key} has no Chinese text");
+        }
+        // Every message looked up in the sources must be in the catalog
+        let used = Regex::new(r#"tr!\(\s*"([^"]+)""#).unwrap();
+        for entry in std::fs::read_dir("src").unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+            for key in used.captures_iter(&source).map(|c| c[1].to_string()) {
+                assert!(keys.contains(key.as_str()), "{key} used in {path:?} is missing");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pick_translation() {
+        let translations = BTreeMap::from([
+            ("en".to_string(), "Use a reference".to_string()),
+            ("zh-CN".to_string(), "使用引用".to_string()),
+        ]);
+        assert_eq!(pick(Lang::ZhCn, translations.clone()).unwrap(), "使用引用");
+        assert_eq!(pick(Lang::En, translations).unwrap(), "Use a reference");
+
+        let only_english = BTreeMap::from([("en".to_string(), "Use a reference".to_string())]);
+        assert_eq!(pick(Lang::ZhCn, only_english).unwrap(), "Use a reference");
+    }
+}
@@ -1,31 +1,37 @@
-use crate::exercise::{Exercise, ExerciseList};
+use crate::config::{ColorChoice, Config, Scope};
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{Exercise, ExerciseList, INFO_TOML_PATH};
 use crate::project::RustAnalyzerProject;
 use crate::run::{reset, run};
 use crate::verify::verify;
+use crate::watch::{poll_interval, watch, Focus, WatchStatus};
 use argh::FromArgs;
 use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, prelude::*};
+use std::io::prelude::*;
 use std::path::Path;
-use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
 use std::time::{UNIX_EPOCH, SystemTime};
+use tokio::sync::Semaphore;
 
 #[macro_use]
-mod ui;
+mod i18n;
 
+mod bench;
+mod config;
+mod diagnostics;
 mod exercise;
+mod expected;
 mod project;
+mod reporter;
 mod run;
+mod toolchain;
+mod tui;
 mod verify;
+mod watch;
+mod worker;
+mod workspace;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -39,6 +45,18 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// language of the messages: en or zh-CN (default: from LANG)
+    #[argh(option)]
+    lang: Option<String>,
+    /// when to color the output: auto, always or never (default: auto, or never with NO_COLOR)
+    #[argh(option)]
+    color: Option<String>,
+    /// the rustup toolchain to compile the exercises with, like `stable` or `1.70`
+    #[argh(option)]
+    toolchain: Option<String>,
+    /// how to report progress: fancy, plain or ndjson (default: fancy on terminals, plain otherwise)
+    #[argh(option)]
+    reporter: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -53,6 +71,8 @@ enum Subcommands {
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
+    Workspace(WorkspaceArgs),
+    Config(ConfigArgs),
     CicvVerify(CicvVerifyArgs)
 }
 
@@ -72,6 +92,18 @@ struct WatchArgs {
     /// show hints on success
     #[argh(switch)]
     success_hints: bool,
+    /// poll the file system for changes instead of relying on OS notifications
+    #[argh(switch)]
+    poll: bool,
+    /// polling interval in milliseconds, at least 1, implies --poll (default: 1000)
+    #[argh(option)]
+    poll_interval: Option<u64>,
+    /// use the line-based shell instead of the full-screen interface
+    #[argh(switch)]
+    no_tui: bool,
+    /// only watch the given exercise, or the exercises of a topic like `smart_pointers`
+    #[argh(option)]
+    only: Option<String>,
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -106,6 +138,56 @@ struct HintArgs {
 /// Enable rust-analyzer for exercises
 struct LspArgs {}
 
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "workspace")]
+/// Generate a Cargo workspace of the exercises for Cargo and IDEs
+struct WorkspaceArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "config")]
+/// Shows or changes the settings in .rustlings.toml
+struct ConfigArgs {
+    #[argh(subcommand)]
+    nested: ConfigSubcommands,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum ConfigSubcommands {
+    List(ConfigListArgs),
+    Get(ConfigGetArgs),
+    Set(ConfigSetArgs),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "list")]
+/// Lists the settings in effect and the files they come from
+struct ConfigListArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "get")]
+/// Prints the value of a setting
+struct ConfigGetArgs {
+    #[argh(positional)]
+    /// the name of the setting
+    key: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "set")]
+/// Changes a setting in the .rustlings.toml of the current directory
+struct ConfigSetArgs {
+    #[argh(positional)]
+    /// the name of the setting
+    key: String,
+    #[argh(positional)]
+    /// the new value
+    value: String,
+    #[argh(switch, short = 'g')]
+    /// change the .rustlings.toml of your home directory instead
+    global: bool,
+}
+
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "list")]
 /// Lists the exercises available in Rustlings
@@ -138,7 +220,9 @@ pub struct ExerciseCheckList {
 #[derive(Deserialize, Serialize)]
 pub struct ExerciseResult {
     pub name: String,
-    pub result: bool
+    pub result: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -153,6 +237,36 @@ pub struct  ExerciseStatistics {
 async fn main() {
     let args: Args = argh::from_env();
 
+    let config = Config::load().unwrap_or_else(|e| {
+        println!("{e}");
+        std::process::exit(1);
+    });
+    if let Err(e) = i18n::init(args.lang.as_deref().or(config.lang.as_deref())) {
+        println!("{e}");
+        std::process::exit(1);
+    }
+    config::init(config);
+    let color = args.color.as_deref().map(|choice| {
+        ColorChoice::parse(choice).unwrap_or_else(|| {
+            println!("{}", tr!("main.unknown_color", choice));
+            std::process::exit(1);
+        })
+    });
+    config::init_colors(color);
+    let reporter = args.reporter.as_deref().map(|name| {
+        reporter::Kind::parse(name).unwrap_or_else(|| {
+            println!("{}", tr!("main.unknown_reporter", name));
+            std::process::exit(1);
+        })
+    });
+    reporter::init(reporter);
+    toolchain::init(args.toolchain.clone());
+
+    if let Some(Subcommands::Config(subargs)) = &args.nested {
+        config_command(&subargs.nested);
+        std::process::exit(0);
+    }
+
     if args.version {
         println!("v{VERSION}");
         std::process::exit(0);
@@ -162,34 +276,46 @@ async fn main() {
         println!("\n{WELCOME}\n");
     }
 
-    if !Path::new("info.toml").exists() {
+    if !Path::new(INFO_TOML_PATH).exists() {
         println!(
-            "{} must be run from the rustlings directory",
-            std::env::current_exe().unwrap().to_str().unwrap()
+            "{}",
+            tr!(
+                "main.wrong_directory",
+                std::env::current_exe().unwrap().to_str().unwrap()
+            )
         );
-        println!("Try `cd rustlings/`!");
         std::process::exit(1);
     }
 
-    if !rustc_exists() {
-        println!("We cannot find `rustc`.");
-        println!("Try running `rustc --version` to diagnose your problem.");
-        println!("For instructions on how to install Rust, check the README.");
+    if toolchain::rustc_version().is_none() {
+        match toolchain::toolchain() {
+            Some(toolchain) => println!("{}", tr!("main.no_toolchain", toolchain)),
+            None => println!("{}", tr!("main.no_rustc")),
+        }
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-    let verbose = args.nocapture;
+    let exercises = ExerciseList::from_file(INFO_TOML_PATH)
+        .unwrap_or_else(|e| {
+            println!("{}", tr!("main.info_toml_unparsable", INFO_TOML_PATH, e));
+            std::process::exit(1);
+        })
+        .exercises;
+    let verbose = args.nocapture || config::get().verbose.unwrap_or(false);
 
     let command = args.nested.unwrap_or_else(|| {
-        println!("{DEFAULT_OUT}\n");
+        println!("{}\n", tr!("main.default_out"));
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
             if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+                println!(
+                    "{:<17}\t{:<46}\t{:<7}",
+                    tr!("list.name"),
+                    tr!("list.path"),
+                    tr!("list.status")
+                );
             }
             let mut exercises_done: u16 = 0;
             let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
@@ -201,9 +327,9 @@ async fn main() {
                     .any(|f| e.name.contains(&f) || fname.contains(&f));
                 let status = if e.looks_done() {
                     exercises_done += 1;
-                    "Done"
+                    tr!("list.done")
                 } else {
-                    "Pending"
+                    tr!("list.pending")
                 };
                 let solve_cond = {
                     (e.looks_done() && subargs.solved)
@@ -235,10 +361,13 @@ async fn main() {
             });
             let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
             println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
+                "{}",
+                tr!(
+                    "main.progress",
+                    exercises_done,
+                    exercises.len(),
+                    format!("{percentage_progress:.1}")
+                )
             );
             std::process::exit(0);
         }
@@ -270,6 +399,8 @@ async fn main() {
             // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
             let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
             let rights = Arc::new(Mutex::new(0));
+            let jobs = config::get().jobs.unwrap_or(Semaphore::MAX_PERMITS);
+            let permits = Arc::new(Semaphore::new(jobs));
             let alls = exercises.len();
 
             let exercise_check_list =  Arc::new(Mutex::new(
@@ -291,30 +422,32 @@ async fn main() {
                 let inner_exercise = exercise;
                 let c_mutex = Arc::clone(&rights);
                 let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
+                let _verbose = verbose;
+                let permits = Arc::clone(&permits);
                 let t = tokio::task::spawn( async move {
+                    let _permit = permits.acquire_owned().await.unwrap();
                     match run(&inner_exercise, true) {
                     // match verify(vec![&inner_exercise], (0, 1), true, true) {
                         Ok(_) => {
                             *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                            println!("{}", tr!("cicv.passed", inner_exercise.name));
+                            println!("{}", tr!("cicv.total", alls));
+                            println!("{}", tr!("cicv.passed_so_far", *c_mutex.lock().unwrap()));
                             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
+                            println!("{}", tr!("cicv.elapsed", now_end - now_start));
                             exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
+                                name: inner_exercise.name, result: true, diagnostics: Vec::new(),
                             });
                             exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
                         },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
+                        Err(diagnostics) => {
+                            println!("{}", tr!("cicv.failed", inner_exercise.name));
+                            println!("{}", tr!("cicv.total", alls));
+                            println!("{}", tr!("cicv.passed_so_far", *c_mutex.lock().unwrap()));
                             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
+                            println!("{}", tr!("cicv.elapsed", now_end - now_start));
                             exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
+                                name: inner_exercise.name, result: false, diagnostics,
                             });
                             exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
                         }
@@ -325,7 +458,7 @@ async fn main() {
             for task in tasks { task.await.unwrap(); }
             let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
             let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
+            println!("{}", tr!("cicv.finished", total_time));
             let exercise_check_list_ref = Arc::clone(&exercise_check_list);
             exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
             let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
@@ -334,91 +467,70 @@ async fn main() {
 
         Subcommands::Lsp(_subargs) => {
             let mut project = RustAnalyzerProject::new();
+            if let Err(e) = project.get_sysroot_src() {
+                println!("{}", tr!("main.no_sysroot"));
+                println!("{e}");
+                std::process::exit(1);
+            }
             project
-                .get_sysroot_src()
-                .expect("Couldn't find toolchain path, do you have `rustc` installed?");
-            project
-                .exercises_to_json()
+                .exercises_to_json(&exercises)
                 .expect("Couldn't parse rustlings exercises files");
 
             if project.crates.is_empty() {
-                println!("Failed find any exercises, make sure you're in the `rustlings` folder");
+                println!("{}", tr!("main.no_exercises_found"));
             } else if project.write_to_disk().is_err() {
-                println!("Failed to write rust-project.json to disk for rust-analyzer");
+                println!("{}", tr!("main.rust_project_unwritable"));
             } else {
-                println!("Successfully generated rust-project.json");
-                println!("rust-analyzer will now parse exercises, restart your language server or editor")
+                println!("{}", tr!("main.rust_project_written"));
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
+        Subcommands::Config(_subargs) => unreachable!("handled before loading the exercises"),
+
+        Subcommands::Workspace(_subargs) => match workspace::generate(&exercises) {
+            Ok(manifest_path) => {
+                println!("{}", tr!("main.workspace_generated", manifest_path.display()));
+            }
             Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                println!("{}", tr!("main.workspace_failed", e));
                 std::process::exit(1);
             }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
-            }
         },
-    }
-}
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
-                    }
-                } else {
-                    println!("unknown command: {input}");
+        Subcommands::Watch(subargs) => {
+            let poll_interval =
+                poll_interval(subargs.poll, subargs.poll_interval).unwrap_or_else(|e| {
+                    println!("{e}");
+                    std::process::exit(1)
+                });
+            let focus = subargs.only.map(|only| {
+                Focus::new(&only, &exercises).unwrap_or_else(|e| {
+                    println!("{e}");
+                    std::process::exit(1)
+                })
+            });
+            match watch(
+                exercises,
+                verbose,
+                subargs.success_hints || config::get().success_hints.unwrap_or(false),
+                poll_interval,
+                !subargs.no_tui,
+                focus,
+            ) {
+                Err(e) => {
+                    println!("{}", tr!("main.watch_failed", format!("{e:?}")));
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!("{}", tr!("main.watch_finished", Emoji("🎉", "★")));
+                    println!("\n{FENISH_LINE}\n");
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("{}", tr!("main.watch_unfinished"));
                 }
             }
-            Err(error) => println!("error reading command: {error}"),
         }
-    });
+    }
 }
 
 fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
@@ -427,8 +539,7 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
             .iter()
             .find(|e| !e.looks_done())
             .unwrap_or_else(|| {
-                println!("🎉 Congratulations! You have done all the exercises!");
-                println!("🔚 There are no more exercises to do next!");
+                println!("{}", tr!("main.all_done"));
                 std::process::exit(1)
             })
     } else {
@@ -436,130 +547,54 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
             .iter()
             .find(|e| e.name == name)
             .unwrap_or_else(|| {
-                println!("No exercise found for '{name}'!");
+                println!("{}", tr!("main.no_exercise", name));
                 std::process::exit(1)
             })
     }
 }
 
-enum WatchStatus {
-    Finished,
-    Unfinished,
-}
-
-fn watch(
-    exercises: &[Exercise],
-    verbose: bool,
-    success_hints: bool,
-) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
-    }
-
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
-
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
-
-    clear_screen();
-
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
-    loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            },
-            Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
+fn config_command(command: &ConfigSubcommands) {
+    let result = match command {
+        ConfigSubcommands::List(_) => list_config(),
+        ConfigSubcommands::Get(subargs) => config::get().get(&subargs.key).map(|value| match value {
+            Some(value) => println!("{value}"),
+            None => {
+                println!("{}", tr!("main.config_unset", subargs.key));
+                std::process::exit(1);
             }
-            Err(e) => println!("watch error: {e:?}"),
-        }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
+        }),
+        ConfigSubcommands::Set(subargs) => {
+            let scope = if subargs.global { Scope::Global } else { Scope::Local };
+            Config::read(scope).and_then(|mut config| {
+                config.set(&subargs.key, &subargs.value)?;
+                let path = config.write(scope)?;
+                println!("{}", tr!("main.config_set", subargs.key, path.display()));
+                Ok(())
+            })
         }
+    };
+    if let Err(e) = result {
+        println!("{e}");
+        std::process::exit(1);
     }
 }
 
-fn rustc_exists() -> bool {
-    Command::new("rustc")
-        .args(&["--version"])
-        .stdout(Stdio::null())
-        .spawn()
-        .and_then(|mut child| child.wait())
-        .map(|status| status.success())
-        .unwrap_or(false)
+// Print the settings that are set, with the file each one comes from
+fn list_config() -> Result<(), String> {
+    let global = Config::read(Scope::Global)?;
+    let local = Config::read(Scope::Local)?;
+    for key in config::KEYS {
+        let (value, scope) = match (local.get(key)?, global.get(key)?) {
+            (Some(value), _) => (value, Scope::Local),
+            (None, Some(value)) => (value, Scope::Global),
+            (None, None) => continue,
+        };
+        let path = scope.path().unwrap_or_default();
+        println!("{key} = {value}\t# {}", path.display());
+    }
+    Ok(())
 }
 
-const DEFAULT_OUT: &str = r#"Thanks for installing Rustlings!
-
-Is this your first time? Don't worry, Rustlings was made for beginners! We are
-going to teach you a lot of things about Rust, but before we can get
-started, here's a couple of notes about how Rustlings operates:
-
-1. The central concept behind Rustlings is that you solve exercises. These
-   exercises usually have some sort of syntax error in them, which will cause
-   them to fail compilation or testing. Sometimes there's a logic error instead
-   of a syntax error. No matter what error, it's your job to find it and fix it!
-   You'll know when you fixed it because then, the exercise will compile and
-   Rustlings will be able to move on to the next exercise.
-2. If you run Rustlings in watch mode (which we recommend), it'll automatically
-   start with the first exercise. Don't get confused by an error message popping
-   up as soon as you run Rustlings! This is part of the exercise that you're
-   supposed to solve, so open the exercise file in an editor and start your
-   detective work!
-3. If you're stuck on an exercise, there is a helpful hint you can view by typing
-   'hint' (in watch mode), or running `rustlings hint exercise_name`.
-4. If an exercise doesn't make sense to you, feel free to open an issue on GitHub!
-   (https://github.com/rust-lang/rustlings/issues/new). We look at every issue,
-   and sometimes, other learners do too so you can help each other out!
-5. If you want to use `rust-analyzer` with exercises, which provides features like
-   autocompletion, run the command `rustlings lsp`.
-
-Got all that? Great! To get started, run `rustlings watch` in order to get the first
-exercise. Make sure to have your editor open!"#;
-
 const FENISH_LINE: &str = r#"+----------------------------------------------------+
 |          You made it to the Fe-nish line!          |
 +--------------------------  ------------------------+
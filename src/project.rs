@@ -1,29 +1,106 @@
-use glob::glob;
+use crate::exercise::{Exercise, Mode, EDITION};
+use crate::toolchain;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::error::Error;
-use std::path::PathBuf;
-use std::process::Command;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
 
 /// Contains the structure of resulting rust-project.json file
 /// and functions to build the data required to create the file
 #[derive(Serialize, Deserialize)]
 pub struct RustAnalyzerProject {
+    sysroot: String,
     sysroot_src: String,
     pub crates: Vec<Crate>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Crate {
+    display_name: String,
     root_module: String,
     edition: String,
     deps: Vec<String>,
     cfg: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+}
+
+/// The `rustc-cfg` and `rustc-env` instructions printed by a build script
+#[derive(Clone, Default, Debug, PartialEq)]
+struct BuildScriptOutput {
+    cfg: Vec<String>,
+    env: BTreeMap<String, String>,
+}
+
+impl BuildScriptOutput {
+    /// Parse the instructions in both the `cargo:` and the newer `cargo::` syntax
+    fn parse(stdout: &str) -> BuildScriptOutput {
+        let mut output = BuildScriptOutput::default();
+        for line in stdout.lines() {
+            let instruction = match line
+                .strip_prefix("cargo::")
+                .or_else(|| line.strip_prefix("cargo:"))
+            {
+                Some(instruction) => instruction,
+                None => continue,
+            };
+            if let Some(cfg) = instruction.strip_prefix("rustc-cfg=") {
+                output.cfg.push(cfg.to_string());
+            } else if let Some((key, value)) = instruction
+                .strip_prefix("rustc-env=")
+                .and_then(|env| env.split_once('='))
+            {
+                output.env.insert(key.to_string(), value.to_string());
+            }
+        }
+        output
+    }
+}
+
+/// Compile and run the build script in `dir`, the way Cargo does before
+/// compiling the exercises of the `buildscript` mode
+fn run_build_script(dir: &Path) -> Result<BuildScriptOutput, Box<dyn Error>> {
+    let out_dir = env::temp_dir().join(format!("rustlings_build_script_{}", process::id()));
+    fs::create_dir_all(&out_dir)?;
+    let output = compile_and_run_build_script(dir, &out_dir);
+    let _ = fs::remove_dir_all(&out_dir);
+    output
+}
+
+fn compile_and_run_build_script(
+    dir: &Path,
+    out_dir: &Path,
+) -> Result<BuildScriptOutput, Box<dyn Error>> {
+    let binary = out_dir.join("build-script-build");
+    let compilation = toolchain::rustc()
+        .arg(dir.join("build.rs"))
+        .args(["--edition", EDITION, "-o"])
+        .arg(&binary)
+        .output()?;
+    if !compilation.status.success() {
+        return Err(tr!("project.build_script_uncompilable").into());
+    }
+
+    let run = Command::new(&binary)
+        .current_dir(dir)
+        .env("OUT_DIR", out_dir)
+        .env("CARGO_MANIFEST_DIR", dir.canonicalize()?)
+        .output()?;
+    if !run.status.success() {
+        return Err(tr!("project.build_script_crashed").into());
+    }
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    Ok(BuildScriptOutput::parse(&stdout))
 }
 
 impl RustAnalyzerProject {
     pub fn new() -> RustAnalyzerProject {
         RustAnalyzerProject {
+            sysroot: String::new(),
             sysroot_src: String::new(),
             crates: Vec::new(),
         }
@@ -38,62 +115,154 @@ impl RustAnalyzerProject {
         Ok(())
     }
 
-    /// If path contains .rs extension, add a crate to `rust-project.json`
-    fn path_to_json(&mut self, path: PathBuf) -> Result<(), Box<dyn Error>> {
-        if let Some(ext) = path.extension() {
-            if ext == "rs" {
-                self.crates.push(Crate {
-                    root_module: path.display().to_string(),
-                    edition: "2021".to_string(),
-                    deps: Vec::new(),
-                    // This allows rust_analyzer to work inside #[test] blocks
-                    cfg: vec!["test".to_string()],
-                })
+    /// Add a crate to `rust-project.json` for every exercise in info.toml,
+    /// configured like `verify` compiles it: tests are only enabled for the
+    /// exercises run as tests, and the exercises of the `buildscript` mode
+    /// see what their build script sets up, which is a crate of its own
+    pub fn exercises_to_json(&mut self, exercises: &[Exercise]) -> Result<(), Box<dyn Error>> {
+        let mut build_scripts: HashMap<PathBuf, BuildScriptOutput> = HashMap::new();
+
+        for exercise in exercises {
+            let mut cfg = Vec::new();
+            let mut env = BTreeMap::new();
+            match exercise.mode {
+                Mode::Compile | Mode::Clippy | Mode::Output | Mode::CompileFail | Mode::Doctest => {}
+                // This allows rust_analyzer to work inside #[test] blocks
+                Mode::Test | Mode::Bench => cfg.push("test".to_string()),
+                Mode::BuildScript => {
+                    let dir = exercise.path.parent().unwrap_or_else(|| Path::new("."));
+                    let output = match build_scripts.get(dir) {
+                        Some(output) => output.clone(),
+                        None => {
+                            let output = self.build_script_to_json(dir);
+                            build_scripts.insert(dir.to_path_buf(), output.clone());
+                            output
+                        }
+                    };
+                    cfg.push("test".to_string());
+                    cfg.extend(output.cfg);
+                    env = output.env;
+                }
             }
+            cfg.extend(exercise.build.cfg.iter().cloned());
+            env.extend(exercise.build.env.clone());
+
+            self.crates.push(Crate {
+                display_name: exercise.name.clone(),
+                root_module: exercise.path.display().to_string(),
+                edition: exercise.edition().to_string(),
+                deps: Vec::new(),
+                cfg,
+                env,
+            });
         }
 
         Ok(())
     }
 
-    /// Parse the exercises folder for .rs files, any matches will create
-    /// a new `crate` in rust-project.json which allows rust-analyzer to
-    /// treat it like a normal binary
-    pub fn exercises_to_json(&mut self) -> Result<(), Box<dyn Error>> {
-        for path in glob("./exercises/**/*")? {
-            self.path_to_json(path?)?;
-        }
-        Ok(())
+    /// Add the build script in `dir` as a crate and return what it prints.
+    /// A build script the learner still has to fix is expected to fail.
+    fn build_script_to_json(&mut self, dir: &Path) -> BuildScriptOutput {
+        let script = dir.join("build.rs");
+        self.crates.push(Crate {
+            display_name: "build-script-build".to_string(),
+            root_module: script.display().to_string(),
+            edition: EDITION.to_string(),
+            deps: Vec::new(),
+            cfg: Vec::new(),
+            env: BTreeMap::new(),
+        });
+
+        run_build_script(dir).unwrap_or_else(|e| {
+            println!(
+                "{}",
+                tr!("project.build_script_failed", script.display(), e)
+            );
+            BuildScriptOutput::default()
+        })
     }
 
-    /// Use `rustc` to determine the default toolchain
+    /// Use `rustc` to determine the active toolchain, which honors rustup's
+    /// overrides like `rust-toolchain.toml` and `RUSTUP_TOOLCHAIN`, and check
+    /// that it has the standard library sources rust-analyzer needs
     pub fn get_sysroot_src(&mut self) -> Result<(), Box<dyn Error>> {
+        let output = toolchain::rustc()
+            .args(["--print", "sysroot"])
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(tr!("project.sysroot_failed", stderr.trim()).into());
+        }
+        // The path may contain spaces, but not line breaks
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let sysroot = PathBuf::from(stdout.lines().next().unwrap_or_default());
+        println!("{}", tr!("project.toolchain", sysroot.display()));
+        self.sysroot = sysroot.display().to_string();
+
         // check if RUST_SRC_PATH is set
         if let Ok(path) = env::var("RUST_SRC_PATH") {
+            if !Path::new(&path).join("core").is_dir() {
+                return Err(tr!("project.bad_rust_src_path", path).into());
+            }
             self.sysroot_src = path;
             return Ok(());
         }
 
-        let toolchain = Command::new("rustc")
-            .arg("--print")
-            .arg("sysroot")
-            .output()?
-            .stdout;
-
-        let toolchain = String::from_utf8_lossy(&toolchain);
-        let mut whitespace_iter = toolchain.split_whitespace();
-
-        let toolchain = whitespace_iter.next().unwrap_or(&toolchain);
-
-        println!("Determined toolchain: {}\n", &toolchain);
-
-        self.sysroot_src = (std::path::Path::new(&*toolchain)
+        let sysroot_src = sysroot
             .join("lib")
             .join("rustlib")
             .join("src")
             .join("rust")
-            .join("library")
-            .to_string_lossy())
-        .to_string();
+            .join("library");
+        if !sysroot_src.join("core").is_dir() {
+            return Err(missing_rust_src(&sysroot).into());
+        }
+        self.sysroot_src = sysroot_src.display().to_string();
         Ok(())
     }
 }
+
+/// Explain how to install the standard library sources for the toolchain at `sysroot`
+fn missing_rust_src(sysroot: &Path) -> String {
+    let rustup_toolchain = sysroot
+        .parent()
+        .filter(|parent| parent.file_name() == Some(OsStr::new("toolchains")))
+        .and(sysroot.file_name())
+        .map(OsStr::to_string_lossy);
+    match rustup_toolchain {
+        Some(toolchain) => tr!("project.no_rust_src_component", toolchain),
+        None => tr!("project.no_rust_src", sysroot.display()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_build_script_output() {
+        let output = BuildScriptOutput::parse(
+            "cargo:rustc-env=TEST_FOO=1700000000\n\
+             cargo::rustc-cfg=feature=\"pass\"\n\
+             cargo:rerun-if-changed=build.rs\n\
+             some other output\n",
+        );
+        assert_eq!(output.cfg, vec![r#"feature="pass""#]);
+        assert_eq!(
+            output.env,
+            BTreeMap::from([("TEST_FOO".to_string(), "1700000000".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_missing_rust_src() {
+        let message = missing_rust_src(Path::new(
+            "/home/ferris/.rustup/toolchains/1.70-x86_64-unknown-linux-gnu",
+        ));
+        assert!(message
+            .contains("rustup component add rust-src --toolchain 1.70-x86_64-unknown-linux-gnu"));
+
+        let message = missing_rust_src(Path::new("/usr"));
+        assert!(message.contains("RUST_SRC_PATH"));
+    }
+}
@@ -0,0 +1,552 @@
+use crate::config;
+use crate::diagnostics::{self, Diagnostic};
+use crate::exercise::{ContextLine, Exercise, ExerciseOutput, Mode};
+use console::{style, Emoji, Term};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+// A step of checking an exercise
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Compile,
+    Run,
+    Test,
+}
+
+impl Step {
+    fn as_str(self) -> &'static str {
+        match self {
+            Step::Compile => "compile",
+            Step::Run => "run",
+            Step::Test => "test",
+        }
+    }
+}
+
+// What happens while exercises are compiled, run and verified
+pub enum Event<'a> {
+    // A step started, which can take a while
+    Started { exercise: &'a Exercise, step: Step },
+    // The exercise compiled
+    Compiled { exercise: &'a Exercise },
+    // What the exercise or its tests printed
+    Output { exercise: &'a Exercise, output: &'a str },
+    // A step failed, with the output of the compiler or of the exercise
+    Failed {
+        exercise: &'a Exercise,
+        step: Step,
+        output: &'a ExerciseOutput,
+    },
+    // The exercise ran, or its tests passed, outside of verification
+    Passed { exercise: &'a Exercise },
+    // The exercise works and is no longer marked as not done
+    Done { exercise: &'a Exercise },
+    // The exercise works, but still has its `I AM NOT DONE` comment.
+    // The hint is only there when it was asked for.
+    Pending {
+        exercise: &'a Exercise,
+        output: Option<&'a str>,
+        hint: Option<&'a str>,
+        context: &'a [ContextLine],
+    },
+    // `done` of `total` exercises are verified
+    Progress { done: usize, total: usize },
+    // Messages that aren't about checking a single exercise
+    Success(&'a str),
+    Warning(&'a str),
+}
+
+impl Event<'_> {
+    fn exercise(&self) -> Option<&Exercise> {
+        match *self {
+            Event::Started { exercise, .. }
+            | Event::Compiled { exercise }
+            | Event::Output { exercise, .. }
+            | Event::Failed { exercise, .. }
+            | Event::Passed { exercise }
+            | Event::Done { exercise }
+            | Event::Pending { exercise, .. } => Some(exercise),
+            Event::Progress { .. } | Event::Success(_) | Event::Warning(_) => None,
+        }
+    }
+}
+
+// Presents the events to the user
+pub trait Reporter: Send + Sync {
+    fn report(&self, event: &Event<'_>);
+}
+
+// The available reporters
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    // Spinners, progress bars, colors and emoji for terminals
+    Fancy,
+    // One line of text after the other, for logs and screen readers
+    Plain,
+    // A JSON object per line, for tools
+    Ndjson,
+}
+
+impl Kind {
+    pub fn parse(name: &str) -> Option<Kind> {
+        match name {
+            "fancy" => Some(Kind::Fancy),
+            "plain" => Some(Kind::Plain),
+            "ndjson" | "json" => Some(Kind::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+static REPORTER: OnceLock<Box<dyn Reporter>> = OnceLock::new();
+
+// Pick the reporter. Without a choice, the fancy reporter is used on
+// terminals, and the plain one when the output goes elsewhere.
+pub fn init(kind: Option<Kind>) {
+    let kind = kind.unwrap_or(if Term::stdout().is_term() {
+        Kind::Fancy
+    } else {
+        Kind::Plain
+    });
+    let reporter: Box<dyn Reporter> = match kind {
+        Kind::Fancy => Box::<Fancy>::default(),
+        Kind::Plain => Box::new(Plain),
+        Kind::Ndjson => Box::new(Ndjson),
+    };
+    let _ = REPORTER.set(reporter);
+}
+
+pub fn report(event: Event<'_>) {
+    REPORTER.get_or_init(|| Box::new(Plain)).report(&event);
+}
+
+pub fn success(message: &str) {
+    report(Event::Success(message));
+}
+
+pub fn warning(message: &str) {
+    report(Event::Warning(message));
+}
+
+fn started_message(exercise: &Exercise, step: Step) -> String {
+    match step {
+        Step::Compile => tr!("verify.compiling", exercise),
+        Step::Run => tr!("verify.running", exercise),
+        Step::Test => tr!("verify.testing", exercise),
+    }
+}
+
+fn failed_message(exercise: &Exercise, step: Step) -> String {
+    match step {
+        Step::Compile if exercise.mode == Mode::CompileFail => {
+            tr!("verify.compile_fail_failed", exercise)
+        }
+        Step::Compile => tr!("verify.compiling_failed", exercise),
+        Step::Run => tr!("verify.ran_with_errors", exercise),
+        Step::Test => tr!("verify.testing_failed", exercise),
+    }
+}
+
+fn passed_message(exercise: &Exercise) -> String {
+    match exercise.mode {
+        Mode::Compile | Mode::Clippy | Mode::Output => tr!("verify.ran", exercise),
+        Mode::Test | Mode::BuildScript | Mode::Doctest | Mode::Bench => {
+            tr!("verify.tested", exercise)
+        }
+        Mode::CompileFail => tr!("verify.failed_as_expected", exercise),
+    }
+}
+
+// The first line of the message about an exercise that works but isn't done yet
+fn pending_message(exercise: &Exercise) -> String {
+    match exercise.mode {
+        Mode::Compile | Mode::Output => tr!("verify.ran", exercise),
+        Mode::Test | Mode::Doctest | Mode::Bench => tr!("verify.tested", exercise),
+        Mode::Clippy | Mode::BuildScript => tr!("verify.compiled", exercise),
+        Mode::CompileFail => tr!("verify.failed_as_expected", exercise),
+    }
+}
+
+fn works_message(exercise: &Exercise, emoji: bool) -> String {
+    match exercise.mode {
+        Mode::Compile => tr!("verify.compiles").to_string(),
+        Mode::Test => tr!("verify.tests_pass").to_string(),
+        Mode::Clippy if emoji => tr!("verify.clippy_happy", "📎 ", " 📎"),
+        Mode::Clippy => tr!("verify.clippy_happy", "", ""),
+        Mode::BuildScript => tr!("verify.build_script_works").to_string(),
+        Mode::Output => tr!("verify.output_matches").to_string(),
+        Mode::CompileFail => tr!("verify.fails_to_compile").to_string(),
+        Mode::Doctest => tr!("verify.doctests_pass").to_string(),
+        Mode::Bench => tr!("verify.fast_enough").to_string(),
+    }
+}
+
+// The reporter for terminals
+#[derive(Default)]
+struct Fancy {
+    // The spinners of the steps in progress, by exercise,
+    // as `cicvverify` checks several exercises at once
+    spinners: Mutex<HashMap<String, ProgressBar>>,
+    // The progress bar of a running verification
+    progress: Mutex<Option<ProgressBar>>,
+}
+
+impl Fancy {
+    fn clear_spinner(&self, exercise: &Exercise) {
+        if let Some(spinner) = self.spinners.lock().unwrap().remove(&exercise.name) {
+            spinner.finish_and_clear();
+        }
+    }
+
+    // Verification stops at an exercise that failed or isn't done yet
+    fn end_progress(&self) {
+        self.progress.lock().unwrap().take();
+    }
+
+    fn print_compiler_output(&self, exercise: &Exercise, output: &ExerciseOutput) {
+        if output.diagnostics.is_empty() {
+            println!("{}", output.stdout);
+            println!("{}", output.stderr);
+        } else {
+            println!("{}", diagnostics::render(&output.diagnostics));
+            // Besides the diagnostics, a compilation may tell why it failed
+            if !output.stdout.is_empty() {
+                println!("{}", output.stdout);
+            }
+        }
+
+        print!("{}", diagnostic_hints(exercise, &output.diagnostics));
+    }
+
+    fn print_pending(
+        &self,
+        exercise: &Exercise,
+        output: Option<&str>,
+        hint: Option<&str>,
+        context: &[ContextLine],
+    ) {
+        print_success(&pending_message(exercise));
+
+        let emoji = config::emoji();
+        let works = works_message(exercise, emoji);
+        println!();
+        if emoji {
+            println!("🎉 🎉  {works} 🎉 🎉")
+        } else {
+            println!("~*~ {works} ~*~")
+        }
+        println!();
+
+        if let Some(output) = output {
+            println!("{}", tr!("verify.output"));
+            println!("{}", separator());
+            println!("{output}");
+            println!("{}", separator());
+            println!();
+        }
+        if let Some(hint) = hint {
+            println!("{}", tr!("verify.hints"));
+            println!("{}", separator());
+            println!("{hint}");
+            println!("{}", separator());
+            println!();
+        }
+
+        println!(
+            "{}",
+            tr!("verify.keep_working", style("`I AM NOT DONE`").bold())
+        );
+        println!();
+        for context_line in context {
+            let formatted_line = if context_line.important {
+                format!("{}", style(&context_line.line).bold())
+            } else {
+                context_line.line.to_string()
+            };
+
+            println!(
+                "{:>2} {}  {}",
+                style(context_line.number).blue().bold(),
+                style("|").blue(),
+                formatted_line
+            );
+        }
+    }
+}
+
+// The hints of the exercise for the error codes among the diagnostics, each
+// followed by an empty line
+pub fn diagnostic_hints(exercise: &Exercise, diagnostics: &[Diagnostic]) -> String {
+    let bulb = if config::emoji() { "💡" } else { "*" };
+    let mut text = String::new();
+    for hint in exercise.diagnostic_hints_for(diagnostics) {
+        text.push_str(&format!(
+            "{bulb} {} {}\n",
+            style(tr!("verify.hint_for", hint.code)).cyan().bold(),
+            hint.hint
+        ));
+        if let Some(see) = &hint.see {
+            text.push_str(&format!(
+                "   {}\n",
+                tr!("verify.covered_in", style(see).bold(), see)
+            ));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn print_success(message: &str) {
+    if config::emoji() {
+        println!("{} {}", style(Emoji("✅", "✓")).green(), style(message).green());
+    } else {
+        println!("{} {}", style("✓").green(), style(message).green());
+    }
+}
+
+fn print_warning(message: &str) {
+    if config::emoji() {
+        println!("{} {}", style(Emoji("⚠️ ", "!")).red(), style(message).red());
+    } else {
+        println!("{} {}", style("!").red(), style(message).red());
+    }
+}
+
+fn separator() -> console::StyledObject<&'static str> {
+    style("====================").bold()
+}
+
+impl Reporter for Fancy {
+    fn report(&self, event: &Event<'_>) {
+        // Whatever comes after a step is printed in place of its spinner
+        let in_progress = matches!(event, Event::Started { .. } | Event::Compiled { .. });
+        if let Some(exercise) = event.exercise().filter(|_| !in_progress) {
+            self.clear_spinner(exercise);
+        }
+
+        match *event {
+            Event::Started { exercise, step } => {
+                let mut spinners = self.spinners.lock().unwrap();
+                let spinner = spinners.entry(exercise.name.clone()).or_insert_with(|| {
+                    let spinner = ProgressBar::new_spinner();
+                    spinner.enable_steady_tick(100);
+                    spinner
+                });
+                spinner.set_message(started_message(exercise, step));
+            }
+            Event::Compiled { .. } | Event::Done { .. } => {}
+            Event::Output { output, .. } => println!("{output}"),
+            Event::Failed {
+                exercise,
+                step,
+                output,
+            } => {
+                self.end_progress();
+                print_warning(&failed_message(exercise, step));
+                match step {
+                    Step::Compile => self.print_compiler_output(exercise, output),
+                    Step::Run => {
+                        println!("{}", output.stdout);
+                        println!("{}", output.stderr);
+                    }
+                    // The test harness reports on stdout, stderr only has
+                    // something to say when the tests didn't get to finish
+                    Step::Test => {
+                        println!("{}", output.stdout);
+                        if !output.stderr.is_empty() {
+                            println!("{}", output.stderr);
+                        }
+                    }
+                }
+            }
+            Event::Passed { exercise } => print_success(&passed_message(exercise)),
+            Event::Pending {
+                exercise,
+                output,
+                hint,
+                context,
+            } => {
+                self.end_progress();
+                self.print_pending(exercise, output, hint, context);
+            }
+            Event::Progress { done, total } => {
+                let mut progress = self.progress.lock().unwrap();
+                let bar = progress.get_or_insert_with(|| {
+                    let bar = ProgressBar::new(total as u64);
+                    bar.set_style(
+                        ProgressStyle::default_bar()
+                            .template(&format!(
+                                "{} [{{bar:60.green/red}}] {{pos}}/{{len}} {{msg}}",
+                                tr!("reporter.progress")
+                            ))
+                            .progress_chars("#>-"),
+                    );
+                    bar
+                });
+                bar.set_position(done as u64);
+                bar.set_message(format!("({:.1} %)", done as f32 / total as f32 * 100.0));
+                if done >= total {
+                    progress.take();
+                }
+            }
+            Event::Success(message) => print_success(message),
+            Event::Warning(message) => print_warning(message),
+        }
+    }
+}
+
+// The reporter for logs and screen readers, without spinners, colors,
+// emoji or box drawing
+struct Plain;
+
+impl Reporter for Plain {
+    fn report(&self, event: &Event<'_>) {
+        match *event {
+            Event::Started { exercise, step } => println!("{}", started_message(exercise, step)),
+            Event::Compiled { .. } | Event::Done { .. } => {}
+            Event::Output { output, .. } => println!("{output}"),
+            Event::Failed {
+                exercise,
+                step,
+                output,
+            } => {
+                println!("{}", failed_message(exercise, step));
+                if step == Step::Compile && !output.diagnostics.is_empty() {
+                    let rendered = diagnostics::render(&output.diagnostics);
+                    println!("{}", console::strip_ansi_codes(&rendered));
+                    if !output.stdout.is_empty() {
+                        println!("{}", output.stdout);
+                    }
+                } else {
+                    println!("{}", output.stdout);
+                    if !output.stderr.is_empty() {
+                        println!("{}", output.stderr);
+                    }
+                }
+                if step == Step::Compile {
+                    for hint in exercise.diagnostic_hints_for(&output.diagnostics) {
+                        println!("{} {}", tr!("verify.hint_for", hint.code), hint.hint);
+                        if let Some(see) = &hint.see {
+                            println!("{}", tr!("verify.covered_in", see, see));
+                        }
+                    }
+                }
+            }
+            Event::Passed { exercise } => println!("{}", passed_message(exercise)),
+            Event::Pending {
+                exercise,
+                output,
+                hint,
+                context,
+            } => {
+                println!("{}", pending_message(exercise));
+                println!("{}", works_message(exercise, false));
+                if let Some(output) = output {
+                    println!("{}", tr!("verify.output"));
+                    println!("{output}");
+                }
+                if let Some(hint) = hint {
+                    println!("{}", tr!("verify.hints"));
+                    println!("{hint}");
+                }
+                println!("{}", tr!("verify.keep_working", "`I AM NOT DONE`"));
+                for context_line in context {
+                    println!("{}: {}", context_line.number, context_line.line);
+                }
+            }
+            Event::Progress { done, total } => println!(
+                "{} {done}/{total} ({:.1} %)",
+                tr!("reporter.progress"),
+                done as f32 / total as f32 * 100.0
+            ),
+            Event::Success(message) | Event::Warning(message) => println!("{message}"),
+        }
+    }
+}
+
+// The reporter for tools, printing every event as a JSON object on a line
+struct Ndjson;
+
+impl Ndjson {
+    fn to_json(event: &Event<'_>) -> Value {
+        let mut value = match *event {
+            Event::Started { step, .. } => json!({ "event": "started", "step": step.as_str() }),
+            Event::Compiled { .. } => json!({ "event": "compiled" }),
+            Event::Output { output, .. } => json!({ "event": "output", "output": output }),
+            Event::Failed {
+                exercise,
+                step,
+                output,
+            } => {
+                let hints: Vec<Value> = exercise
+                    .diagnostic_hints_for(&output.diagnostics)
+                    .into_iter()
+                    .map(|hint| json!({ "code": hint.code, "hint": hint.hint, "see": hint.see }))
+                    .collect();
+                json!({
+                    "event": "failed",
+                    "step": step.as_str(),
+                    "stdout": output.stdout,
+                    "stderr": output.stderr,
+                    "diagnostics": output.diagnostics,
+                    "hints": hints,
+                })
+            }
+            Event::Passed { .. } => json!({ "event": "passed" }),
+            Event::Done { .. } => json!({ "event": "done" }),
+            Event::Pending {
+                output,
+                hint,
+                context,
+                ..
+            } => {
+                let context: Vec<Value> = context
+                    .iter()
+                    .map(|line| json!({ "number": line.number, "line": line.line, "important": line.important }))
+                    .collect();
+                json!({ "event": "pending", "output": output, "hint": hint, "context": context })
+            }
+            Event::Progress { done, total } => json!({ "event": "progress", "done": done, "total": total }),
+            Event::Success(message) => json!({ "event": "success", "message": message }),
+            Event::Warning(message) => json!({ "event": "warning", "message": message }),
+        };
+        if let Some(exercise) = event.exercise() {
+            value["exercise"] = json!(exercise.name);
+            value["path"] = json!(exercise.path);
+        }
+        value
+    }
+}
+
+impl Reporter for Ndjson {
+    fn report(&self, event: &Event<'_>) {
+        println!("{}", Ndjson::to_json(event));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ndjson() {
+        let exercise = Exercise::for_test(
+            "move_semantics1",
+            "exercises/move_semantics/move_semantics1.rs",
+            Mode::Compile,
+        );
+        let started = Ndjson::to_json(&Event::Started {
+            exercise: &exercise,
+            step: Step::Compile,
+        });
+        assert_eq!(
+            started.to_string(),
+            r#"{"event":"started","exercise":"move_semantics1","path":"exercises/move_semantics/move_semantics1.rs","step":"compile"}"#
+        );
+
+        let progress = Ndjson::to_json(&Event::Progress { done: 3, total: 94 });
+        assert_eq!(progress.to_string(), r#"{"done":3,"event":"progress","total":94}"#);
+    }
+}
@@ -1,19 +1,22 @@
 use std::process::Command;
 
+use crate::diagnostics::Diagnostic;
 use crate::exercise::{Exercise, Mode};
-use crate::verify::test;
-use indicatif::ProgressBar;
+use crate::reporter::{report, Event, Step};
+use crate::verify::{compile, test};
 
 // Invoke the rust compiler on the path of the given exercise,
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+// A compilation failure carries the compiler diagnostics.
+pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), Vec<Diagnostic>> {
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
+        Mode::Test | Mode::Doctest | Mode::Bench => test(exercise, verbose)?,
+        Mode::Compile | Mode::Output => compile_and_run(exercise)?,
         Mode::Clippy => compile_and_run(exercise)?,
         Mode::BuildScript => test(exercise, verbose)?,
+        Mode::CompileFail => compile_only(exercise)?,
     }
     Ok(())
 }
@@ -31,44 +34,48 @@ pub fn reset(exercise: &Exercise) -> Result<(), ()> {
     }
 }
 
+// Invoke the rust compiler on the path of the given exercise,
+// which is expected to fail
+fn compile_only(exercise: &Exercise) -> Result<(), Vec<Diagnostic>> {
+    report(Event::Started {
+        exercise,
+        step: Step::Compile,
+    });
+    compile(exercise)?;
+    report(Event::Passed { exercise });
+    Ok(())
+}
+
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
+fn compile_and_run(exercise: &Exercise) -> Result<(), Vec<Diagnostic>> {
+    report(Event::Started {
+        exercise,
+        step: Step::Compile,
+    });
+    let compilation = compile(exercise)?;
 
-    let compilation_result = exercise.compile();
-    let compilation = match compilation_result {
-        Ok(compilation) => compilation,
-        Err(output) => {
-            progress_bar.finish_and_clear();
-            warn!(
-                "Compilation of {} failed!, Compiler error message:\n",
-                exercise
-            );
-            println!("{}", output.stderr);
-            return Err(());
-        }
-    };
-
-    progress_bar.set_message(format!("Running {exercise}..."));
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
-
-    match result {
+    report(Event::Started {
+        exercise,
+        step: Step::Run,
+    });
+    match compilation.run() {
         Ok(output) => {
-            println!("{}", output.stdout);
-            success!("Successfully ran {}", exercise);
+            report(Event::Output {
+                exercise,
+                output: &output.stdout,
+            });
+            report(Event::Passed { exercise });
             Ok(())
         }
         Err(output) => {
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-
-            warn!("Ran {} with errors", exercise);
-            Err(())
+            report(Event::Failed {
+                exercise,
+                step: Step::Run,
+                output: &output,
+            });
+            Err(Vec::new())
         }
     }
 }
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Deserializer};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::process::Command;
+use std::sync::OnceLock;
+
+// The rustup toolchain chosen with `--toolchain`, like `stable` or `1.70`
+static TOOLCHAIN: OnceLock<String> = OnceLock::new();
+static RUSTC_VERSION: OnceLock<Option<Version>> = OnceLock::new();
+
+pub fn init(toolchain: Option<String>) {
+    if let Some(toolchain) = toolchain {
+        let _ = TOOLCHAIN.set(toolchain);
+    }
+}
+
+pub fn toolchain() -> Option<&'static str> {
+    TOOLCHAIN.get().map(String::as_str)
+}
+
+// A command running `program`, or the one the environment variable points to.
+// The chosen toolchain is selected through rustup's `+toolchain` argument,
+// unless the program was overridden.
+fn command(program: &str, var: &str) -> Command {
+    match env::var_os(var).filter(|program| !program.is_empty()) {
+        Some(program) => Command::new(program),
+        None => {
+            let mut command = Command::new(program);
+            if let Some(toolchain) = toolchain() {
+                command.arg(format!("+{toolchain}"));
+            }
+            command
+        }
+    }
+}
+
+// A command running rustc, `$RUSTC` if set
+pub fn rustc() -> Command {
+    command("rustc", "RUSTC")
+}
+
+// A command running cargo, `$CARGO` if set
+pub fn cargo() -> Command {
+    command("cargo", "CARGO")
+}
+
+// A command running rustdoc, `$RUSTDOC` if set
+pub fn rustdoc() -> Command {
+    command("rustdoc", "RUSTDOC")
+}
+
+// The version of the rustc in use, if it runs
+pub fn rustc_version() -> Option<Version> {
+    *RUSTC_VERSION.get_or_init(|| {
+        let output = rustc().arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // Like `rustc 1.70.0 (90c541806 2023-05-31)`
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout.split_whitespace().nth(1).and_then(Version::parse)
+    })
+}
+
+// A Rust release, ignoring pre-release tags like `-nightly`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    // Parse versions like `1.70`, `1.70.0` or `1.72.0-nightly`
+    pub fn parse(version: &str) -> Option<Version> {
+        let release = version.split(['-', '+']).next()?;
+        let mut parts = release.split('.').map(str::parse);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = match parts.next() {
+            Some(patch) => patch.ok()?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Version {
+            major,
+            minor,
+            patch,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Version, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        Version::parse(&version).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid Rust version '{version}', expected one like \"1.70\""
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        let version = |major, minor, patch| Version {
+            major,
+            minor,
+            patch,
+        };
+        assert_eq!(Version::parse("1.70"), Some(version(1, 70, 0)));
+        assert_eq!(Version::parse("1.70.1"), Some(version(1, 70, 1)));
+        assert_eq!(Version::parse("1.72.0-nightly"), Some(version(1, 72, 0)));
+        assert_eq!(Version::parse("1"), None);
+        assert_eq!(Version::parse("1.x"), None);
+        assert_eq!(Version::parse("1.70.0.1"), None);
+        assert!(version(1, 70, 1) > version(1, 70, 0));
+        assert!(version(1, 9, 0) < version(1, 70, 0));
+    }
+}
@@ -0,0 +1,806 @@
+use crate::diagnostics;
+use crate::exercise::{ContextLine, Exercise, ExerciseList, State, INFO_TOML_PATH};
+use crate::reporter;
+use crate::run::reset;
+use crate::toolchain;
+use crate::verify::{check, Check};
+use crate::watch::{
+    absolute, classify, explainable_code, normalize, open_in_editor, parse_command, pending,
+    shell_command, Change, FileEvent, Focus, Selection, ShellCommand, WatchStatus,
+};
+use crate::worker::{self, Job};
+use console::{style, Key, Term};
+use notify::DebouncedEvent;
+use std::io;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// How often the screen checks for key presses and terminal resizes
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Everything shown on the screen
+#[derive(Default)]
+struct View {
+    // The path of the exercise the learner is working on
+    exercise: Option<String>,
+    // The number of exercises done and the total number of exercises
+    progress: (usize, usize),
+    // The lines around the `I AM NOT DONE` comment
+    context: Vec<ContextLine>,
+    // A one line summary of the last check
+    outcome: String,
+    // Compiler diagnostics, or the output of the binary or test harness
+    output: String,
+    hint: String,
+    show_hint: bool,
+    // A transient message shown above the key bindings
+    message: Option<String>,
+    // The command being typed after `:`, shown instead of the message
+    prompt: Option<String>,
+}
+
+fn separator(title: &str, width: usize) -> String {
+    let rest = width.saturating_sub(title.chars().count() + 4);
+    format!(
+        "{}",
+        style(format!("── {title} {}", "─".repeat(rest))).blue().bold()
+    )
+}
+
+fn progress_bar(done: usize, total: usize, width: usize) -> String {
+    let percentage = if total == 0 {
+        100.0
+    } else {
+        done as f32 / total as f32 * 100.0
+    };
+    let label = format!(" {done}/{total} ({percentage:.1} %)");
+    let bar_width = width.saturating_sub(label.len() + 12).max(10);
+    let filled = (bar_width * done).checked_div(total).unwrap_or(bar_width);
+    let head = if filled < bar_width { ">" } else { "" };
+    let empty = bar_width.saturating_sub(filled + head.len());
+    format!(
+        "{} [{}{}{}]{label}",
+        tr!("reporter.progress"),
+        style("#".repeat(filled)).green(),
+        style(head).green(),
+        style("-".repeat(empty)).red()
+    )
+}
+
+// Lay the view out into exactly `height` lines of at most `width` columns
+fn render(view: &View, width: usize, height: usize) -> Vec<String> {
+    let mut top = vec![
+        format!(
+            "{} {}",
+            style("rustlings watch").bold(),
+            view.exercise.as_deref().unwrap_or_default()
+        ),
+        progress_bar(view.progress.0, view.progress.1, width),
+        separator(tr!("tui.exercise"), width),
+    ];
+    for context_line in &view.context {
+        let line = if context_line.important {
+            format!("{}", style(&context_line.line).bold())
+        } else {
+            context_line.line.clone()
+        };
+        top.push(format!(
+            "{:>3} {}  {line}",
+            style(context_line.number).blue().bold(),
+            style("|").blue()
+        ));
+    }
+    top.push(view.outcome.clone());
+
+    let mut bottom = Vec::new();
+    if view.show_hint {
+        bottom.push(separator(tr!("tui.hint"), width));
+        let hint_lines: Vec<&str> = view.hint.lines().collect();
+        let max_hint_lines = height / 3;
+        bottom.extend(hint_lines.iter().take(max_hint_lines).map(|l| l.to_string()));
+    }
+    bottom.push(String::new());
+    bottom.push(match &view.prompt {
+        Some(prompt) => format!(":{prompt}{}", style(" ").reverse()),
+        None => view.message.clone().unwrap_or_default(),
+    });
+    bottom.push(format!("{}", style(tr!("tui.key_bindings")).dim()));
+
+    let mut lines = top;
+    lines.push(separator(tr!("tui.output"), width));
+    let available = height.saturating_sub(lines.len() + bottom.len());
+    let output_lines: Vec<&str> = view.output.lines().collect();
+    lines.extend(output_lines.iter().take(available).map(|l| l.to_string()));
+    if output_lines.len() > available && available > 0 {
+        lines.pop();
+        lines.push(format!(
+            "{}",
+            style(tr!("tui.more_lines", output_lines.len() - available + 1)).dim()
+        ));
+    }
+    while lines.len() + bottom.len() < height {
+        lines.push(String::new());
+    }
+    lines.extend(bottom);
+    lines.truncate(height);
+    lines
+        .iter()
+        .map(|line| {
+            // `truncate_str` also cuts lines that fit exactly, so only call it
+            // for lines that are actually too wide.
+            if console::measure_text_width(line) > width {
+                console::truncate_str(line, width, "…").into_owned()
+            } else {
+                line.clone()
+            }
+        })
+        .collect()
+}
+
+fn draw(term: &Term, view: &View) -> io::Result<()> {
+    let (height, width) = term.size();
+    let lines = render(view, width as usize, height as usize);
+    term.move_cursor_to(0, 0)?;
+    for (i, line) in lines.iter().enumerate() {
+        term.clear_line()?;
+        if i + 1 < lines.len() {
+            term.write_line(line)?;
+        } else {
+            term.write_str(line)?;
+        }
+    }
+    term.flush()
+}
+
+// What the learner typed
+#[derive(Debug, PartialEq)]
+enum Input {
+    // A key press outside of the `:` prompt
+    Key(Key),
+    // The command typed at the prompt so far, `None` once it is cancelled
+    Prompt(Option<String>),
+    // A command entered at the prompt
+    Command(String),
+}
+
+// Read single key presses on a separate thread, as reading blocks. Typing
+// at the `:` prompt is handled here, so that the thread knows when `q` or
+// `:quit` ends it, leaving the terminal in its normal mode.
+fn spawn_key_reader(stop: Arc<AtomicBool>) -> Receiver<Input> {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let term = Term::stdout();
+        let mut prompt = None;
+        while !stop.load(Ordering::SeqCst) {
+            let Ok(key) = term.read_key() else {
+                break;
+            };
+            let Some(input) = type_key(&mut prompt, key) else {
+                continue;
+            };
+            let quit = match &input {
+                Input::Key(key) => *key == Key::Char('q'),
+                Input::Command(command) => command.trim() == "quit",
+                Input::Prompt(_) => false,
+            };
+            if tx.send(input).is_err() || quit {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+// Apply a key press to the command being typed at the prompt, if any.
+// Returns `None` for keys that don't do anything at the prompt.
+fn type_key(prompt: &mut Option<String>, key: Key) -> Option<Input> {
+    let Some(command) = prompt else {
+        if key == Key::Char(':') {
+            *prompt = Some(String::new());
+            return Some(Input::Prompt(prompt.clone()));
+        }
+        return Some(Input::Key(key));
+    };
+    match key {
+        Key::Enter => return prompt.take().map(Input::Command),
+        Key::Escape => *prompt = None,
+        Key::Backspace => {
+            command.pop();
+        }
+        Key::Char(c) => command.push(c),
+        _ => return None,
+    }
+    Some(Input::Prompt(prompt.clone()))
+}
+
+// The first of `exercises` that is not done, with how its check went
+fn first_not_done<'a>(
+    exercises: impl IntoIterator<Item = &'a Exercise>,
+    verbose: bool,
+) -> Option<(Exercise, Check)> {
+    for exercise in exercises {
+        match check(exercise, verbose) {
+            Check::Done => continue,
+            check => return Some((exercise.clone(), check)),
+        }
+    }
+    None
+}
+
+struct Tui {
+    term: Term,
+    view: View,
+    selection: Selection,
+    verbose: bool,
+    success_hints: bool,
+    // The check running in the background, if any
+    job: Option<Job<Option<(Exercise, Check)>>>,
+    // Whether the running check is of an exercise asked for with `:run`,
+    // which leaves the current exercise alone
+    running_other: bool,
+    // The error code of the last compilation failure, for `:explain`
+    last_error_code: Option<String>,
+}
+
+impl Tui {
+    fn redraw(&self) {
+        let _ = draw(&self.term, &self.view);
+    }
+
+    fn set_message(&mut self, message: String) {
+        self.view.message = Some(message);
+        self.redraw();
+    }
+
+    fn cancel(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancel();
+        }
+    }
+
+    // Run `check` in the background, cancelling the check that was running
+    fn start(
+        &mut self,
+        running_other: bool,
+        check: impl FnOnce() -> Option<(Exercise, Check)> + Send + 'static,
+    ) {
+        self.cancel();
+        self.view.progress = self.selection.progress();
+        self.job = Some(Job::spawn(check));
+        self.running_other = running_other;
+        self.set_message(tr!("tui.checking").to_string());
+    }
+
+    // Check the pending exercises in order in the background, starting with
+    // the one at `changed` if any. The first exercise that is not done is
+    // shown once the check is through.
+    fn check_pending(&mut self, changed: Option<&Path>) {
+        let exercises = self.selection.exercises.clone();
+        let changed = changed.map(Path::to_path_buf);
+        let skipped = self.selection.skipped.clone();
+        let verbose = self.verbose;
+        self.start(false, move || {
+            let pending = pending(&exercises, changed.as_deref(), &skipped);
+            first_not_done(pending, verbose)
+        });
+    }
+
+    // Check all exercises from the start, whether solved or not.
+    // With `--only`, that's all exercises of the focus.
+    fn check_all(&mut self) {
+        let exercises = self.selection.exercises.clone();
+        let focus = self.selection.focus.clone();
+        let verbose = self.verbose;
+        self.start(false, move || {
+            let all = match &focus {
+                Some(focus) => focus.exercises(&exercises),
+                None => exercises.iter().collect(),
+            };
+            first_not_done(all.into_iter().filter(|e| e.path.exists()), verbose)
+        });
+    }
+
+    // Check a single exercise and show it, leaving the current exercise alone
+    fn check_other(&mut self, exercise: Exercise) {
+        let verbose = self.verbose;
+        self.start(true, move || {
+            let check = check(&exercise, verbose);
+            Some((exercise, check))
+        });
+    }
+
+    // Check again after the file at `changed` was edited, or after anything
+    // else may have changed. With `--only`, the current exercise of the focus
+    // is checked instead of all pending exercises.
+    fn recheck(&mut self, changed: Option<&Path>) {
+        if self.selection.refocus(changed) {
+            self.check_focused();
+        } else {
+            self.check_pending(changed);
+        }
+    }
+
+    // Check the current exercise of the focus, whether solved or not
+    fn check_focused(&mut self) {
+        let Some(exercise) = self.selection.focused() else {
+            return;
+        };
+        let verbose = self.verbose;
+        self.start(false, move || {
+            let check = check(&exercise, verbose);
+            Some((exercise, check))
+        });
+    }
+
+    // Move on to the next exercise of the focus
+    fn move_on(&mut self) {
+        match self.selection.move_on() {
+            Ok(()) => self.check_focused(),
+            Err(message) => self.set_message(message),
+        }
+    }
+
+    // Show the outcome of the check once it is through.
+    // Returns whether all exercises are done.
+    fn poll_job(&mut self) -> bool {
+        if !self.job.as_ref().is_some_and(Job::is_finished) {
+            return false;
+        }
+        match self.job.take().and_then(Job::join) {
+            Some(Some((exercise, check))) => {
+                if !self.running_other {
+                    self.selection.current_exercise = Some(exercise.path.clone());
+                }
+                self.show(&exercise, check);
+            }
+            // With `--only`, all exercises of the focus are done after `:verify all`
+            Some(None) if self.selection.focus.is_some() => self.recheck(None),
+            Some(None) => return self.show_all_done(),
+            None => self.set_message(format!("{}", style(tr!("tui.check_failed")).red())),
+        }
+        false
+    }
+
+    fn show(&mut self, exercise: &Exercise, check: Check) {
+        let (works, outcome, output) = match check {
+            Check::Done => (
+                true,
+                format!(
+                    "{} {}",
+                    style(tr!("tui.done", exercise)).green().bold(),
+                    style(tr!("tui.press_next")).bold()
+                ),
+                String::new(),
+            ),
+            Check::Pending(output) => (
+                true,
+                format!(
+                    "{} {}",
+                    style(tr!("tui.works")).green().bold(),
+                    style(tr!("tui.remove_comment")).bold()
+                ),
+                output.unwrap_or_default(),
+            ),
+            Check::Failed(output) => {
+                if let Some(code) = explainable_code(&output.diagnostics) {
+                    self.last_error_code = Some(code);
+                }
+                // The hints come first, so that a long output doesn't cut them off
+                let mut rendered = reporter::diagnostic_hints(exercise, &output.diagnostics);
+                if output.diagnostics.is_empty() {
+                    rendered.push_str(&format!("{}{}", output.stdout, output.stderr));
+                } else {
+                    rendered.push_str(&diagnostics::render(&output.diagnostics));
+                }
+                (
+                    false,
+                    format!("{}", style(tr!("tui.failed")).red().bold()),
+                    rendered,
+                )
+            }
+        };
+        self.view.exercise = Some(exercise.to_string());
+        self.view.context = match exercise.state() {
+            State::Pending(context) => context,
+            State::Done => Vec::new(),
+        };
+        self.view.outcome = outcome;
+        self.view.output = output;
+        self.view.hint = exercise.hint.clone();
+        self.view.show_hint = self.success_hints && works;
+        self.view.message = None;
+        self.redraw();
+    }
+
+    // Returns whether all exercises are done, rather than only the ones not skipped
+    fn show_all_done(&mut self) -> bool {
+        self.selection.current_exercise = None;
+        if self.selection.skipped.is_empty() {
+            return true;
+        }
+        self.view.exercise = None;
+        self.view.context = Vec::new();
+        self.view.output = String::new();
+        self.view.outcome = format!(
+            "{}",
+            style(format!(
+                "{} {}",
+                tr!("watch.skipped_done", self.selection.skipped.len()),
+                tr!("watch.restart_for_skipped")
+            ))
+            .green()
+        );
+        self.view.message = None;
+        self.redraw();
+        false
+    }
+
+    fn handle_file_event(&mut self, event: FileEvent, info_toml: &Path) {
+        match event {
+            FileEvent::Changed(path) => {
+                let filepath = absolute(&path);
+                match classify(&filepath, info_toml) {
+                    Change::Exercise if !self.selection.ignores(&filepath) => {
+                        self.recheck(Some(&filepath))
+                    }
+                    Change::ExerciseList => match ExerciseList::from_file(INFO_TOML_PATH) {
+                        Ok(list) => {
+                            self.selection.exercises = list.exercises;
+                            self.recheck(None);
+                            self.set_message(tr!("tui.reloaded", INFO_TOML_PATH));
+                        }
+                        Err(e) => self.set_message(format!(
+                            "{} {e}",
+                            style(tr!("tui.reload_failed")).red()
+                        )),
+                    },
+                    Change::Exercise | Change::Irrelevant => {}
+                }
+            }
+            FileEvent::Removed(path) => {
+                let filepath = absolute(&path);
+                let exercises = &self.selection.exercises;
+                if let Some(exercise) = exercises.iter().find(|e| filepath.ends_with(&e.path)) {
+                    let message = format!(
+                        "{} {}",
+                        style(tr!("watch.exercise_removed", exercise)).red(),
+                        tr!("watch.restore_with", exercise.name)
+                    );
+                    self.set_message(message);
+                }
+            }
+            FileEvent::Rescan => self.recheck(None),
+        }
+    }
+
+    fn toggle_hint(&mut self) {
+        self.view.show_hint = !self.view.show_hint;
+        self.redraw();
+    }
+
+    // Leave the current exercise for later and show the next pending one
+    fn skip(&mut self) {
+        match self.selection.skip() {
+            Ok(()) => self.check_pending(None),
+            Err(message) => self.set_message(message),
+        }
+    }
+
+    // Show text like the output of a command in the output pane
+    fn show_output(&mut self, output: String) {
+        self.view.output = output;
+        self.view.message = None;
+        self.redraw();
+    }
+
+    fn find(&mut self, name: &str) -> Option<Exercise> {
+        let exercise = self
+            .selection
+            .exercises
+            .iter()
+            .find(|e| e.name == name)
+            .cloned();
+        if exercise.is_none() {
+            self.set_message(tr!("main.no_exercise", name));
+        }
+        exercise
+    }
+
+    // Run a command entered at the `:` prompt, the same ones as the watch shell's.
+    // Returns whether watch mode should end, and how.
+    fn run_command(&mut self, input: &str) -> Option<WatchStatus> {
+        let input = input.trim();
+        if let Some(command) = parse_command(input) {
+            self.handle_command(command);
+            return None;
+        }
+        match input {
+            "" => self.set_message(String::new()),
+            "hint" => self.toggle_hint(),
+            "clear" => {
+                let _ = self.term.clear_screen();
+                self.redraw();
+            }
+            "quit" => return self.handle_key(Key::Char('q')),
+            "help" => self.show_output(tr!("watch.help").to_string()),
+            _ => {
+                if let Some(cmd) = input.strip_prefix('!') {
+                    let output = shell_command(cmd).and_then(|mut command| {
+                        command
+                            .stdin(Stdio::null())
+                            .output()
+                            .map_err(|e| tr!("watch.command_failed", cmd, e))
+                    });
+                    match output {
+                        Ok(output) => self.show_output(format!(
+                            "{}{}",
+                            String::from_utf8_lossy(&output.stdout),
+                            String::from_utf8_lossy(&output.stderr)
+                        )),
+                        Err(e) => self.set_message(e),
+                    }
+                } else if input.split_whitespace().next() == Some("run") {
+                    self.set_message(tr!("watch.run_usage").to_string());
+                } else {
+                    self.set_message(tr!("watch.unknown_command", input));
+                }
+            }
+        }
+        None
+    }
+
+    fn handle_command(&mut self, command: ShellCommand) {
+        match command {
+            ShellCommand::List => self.show_output(self.selection.list()),
+            ShellCommand::Next | ShellCommand::Skip if self.selection.focus.is_some() => {
+                self.move_on()
+            }
+            ShellCommand::Next => {
+                let selection = &self.selection;
+                match pending(&selection.exercises, None, &selection.skipped)
+                    .first()
+                    .map(|e| (*e).clone())
+                {
+                    Some(exercise) => self.check_other(exercise),
+                    None => self.set_message(tr!("watch.nothing_next").to_string()),
+                }
+            }
+            ShellCommand::Run(name) => {
+                if let Some(exercise) = self.find(&name) {
+                    self.check_other(exercise);
+                }
+            }
+            ShellCommand::Reset(name) => {
+                let exercise = match &name {
+                    Some(name) => self.find(name),
+                    None => self.selection.current().cloned(),
+                };
+                // The reset file is checked again like any edited one
+                match exercise {
+                    Some(exercise) if reset(&exercise).is_err() => {
+                        self.set_message(tr!("watch.reset_failed", exercise))
+                    }
+                    None if name.is_none() => {
+                        self.set_message(tr!("watch.nothing_to_reset").to_string())
+                    }
+                    Some(_) | None => {}
+                }
+            }
+            ShellCommand::Skip => self.skip(),
+            ShellCommand::VerifyAll => self.check_all(),
+            ShellCommand::Explain => match self.last_error_code.clone() {
+                Some(code) => match toolchain::rustc().args(["--explain", &code]).output() {
+                    Ok(output) => self.show_output(String::from_utf8_lossy(&output.stdout).into()),
+                    Err(e) => self.set_message(tr!("watch.explain_failed", code, e)),
+                },
+                None => self.set_message(tr!("watch.no_error_code").to_string()),
+            },
+            ShellCommand::Edit(name) => {
+                let exercise = match &name {
+                    Some(name) => self.find(name),
+                    None => self.selection.current().cloned(),
+                };
+                match exercise {
+                    Some(exercise) => {
+                        if let Err(e) = open_in_editor(&exercise.path) {
+                            self.set_message(e);
+                        }
+                    }
+                    None if name.is_none() => {
+                        self.set_message(tr!("watch.nothing_to_edit").to_string())
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    fn handle_input(&mut self, input: Input) -> Option<WatchStatus> {
+        match input {
+            Input::Key(key) => return self.handle_key(key),
+            Input::Prompt(prompt) => {
+                self.view.prompt = prompt;
+                self.redraw();
+            }
+            Input::Command(command) => {
+                self.view.prompt = None;
+                self.view.message = None;
+                self.redraw();
+                return self.run_command(&command);
+            }
+        }
+        None
+    }
+
+    // Returns whether watch mode should end, and how
+    fn handle_key(&mut self, key: Key) -> Option<WatchStatus> {
+        match key {
+            Key::Char('h') => self.toggle_hint(),
+            Key::Char('n') if self.selection.focus.is_some() => self.move_on(),
+            Key::Char('n') => self.skip(),
+            Key::Char('r') => self.recheck(None),
+            Key::Char('e') => match &self.selection.current_exercise {
+                Some(path) => {
+                    if let Err(e) = open_in_editor(path) {
+                        self.set_message(e);
+                    }
+                }
+                None => self.set_message(tr!("watch.nothing_to_edit").to_string()),
+            },
+            Key::Char('q') => {
+                self.cancel();
+                return Some(WatchStatus::Unfinished);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+// Run watch mode as a full-screen interface controlled with single key presses
+pub fn watch(
+    exercises: Vec<Exercise>,
+    verbose: bool,
+    success_hints: bool,
+    rx: Receiver<DebouncedEvent>,
+    info_toml: &Path,
+    focus: Option<Focus>,
+) -> io::Result<WatchStatus> {
+    let term = Term::stdout();
+    let stop = Arc::new(AtomicBool::new(false));
+    let keys = spawn_key_reader(Arc::clone(&stop));
+    let mut tui = Tui {
+        term,
+        view: View::default(),
+        selection: Selection::new(exercises, focus),
+        verbose,
+        success_hints,
+        job: None,
+        running_other: false,
+        last_error_code: None,
+    };
+
+    tui.term.clear_screen()?;
+    worker::take_terminal(true);
+    tui.term.hide_cursor()?;
+    let mut size = tui.term.size();
+    let mut status = None;
+    tui.recheck(None);
+
+    while status.is_none() {
+        match rx.recv_timeout(KEY_POLL_INTERVAL) {
+            Ok(event) => {
+                for event in normalize(event) {
+                    tui.handle_file_event(event, info_toml);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                tui.cancel();
+                status = Some(WatchStatus::Unfinished);
+            }
+        }
+        for input in keys.try_iter() {
+            if status.is_none() {
+                status = tui.handle_input(input);
+            }
+        }
+        if status.is_none() && tui.poll_job() {
+            status = Some(WatchStatus::Finished);
+        }
+        if tui.term.size() != size {
+            size = tui.term.size();
+            tui.term.clear_screen()?;
+            tui.redraw();
+        }
+    }
+
+    if let Some(WatchStatus::Finished) = status {
+        // The key reader is still waiting for a key in raw mode,
+        // let it finish so the terminal is restored
+        stop.store(true, Ordering::SeqCst);
+        tui.set_message(format!(
+            "{}",
+            style(tr!("tui.all_done")).green().bold()
+        ));
+        let _ = keys.recv();
+    }
+    tui.term.show_cursor()?;
+    tui.term.clear_screen()?;
+    worker::take_terminal(false);
+    Ok(status.unwrap_or(WatchStatus::Unfinished))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render_fills_screen() {
+        let view = View {
+            exercise: Some("exercises/intro/intro1.rs".into()),
+            progress: (1, 4),
+            context: vec![ContextLine {
+                line: "// I AM NOT DONE".into(),
+                number: 3,
+                important: true,
+            }],
+            outcome: "failed".into(),
+            output: (1..=50).map(|i| format!("line {i}\n")).collect(),
+            hint: "Remove the comment".into(),
+            show_hint: true,
+            message: None,
+            prompt: None,
+        };
+        let lines: Vec<String> = render(&view, 60, 20)
+            .iter()
+            .map(|l| console::strip_ansi_codes(l).into_owned())
+            .collect();
+
+        assert_eq!(lines.len(), 20);
+        assert!(lines.iter().all(|l| console::measure_text_width(l) <= 60));
+        assert!(lines[1].contains("1/4 (25.0 %)"));
+        assert!(lines.iter().any(|l| l.contains("3 |  // I AM NOT DONE")));
+        assert!(lines.iter().any(|l| l.contains("more lines")));
+        assert!(lines.iter().any(|l| l == "Remove the comment"));
+        assert_eq!(lines[19], tr!("tui.key_bindings"));
+    }
+
+    #[test]
+    fn test_type_key_at_prompt() {
+        let mut prompt = None;
+        let mut type_keys = |keys: &[Key]| {
+            let inputs: Vec<Option<Input>> = keys
+                .iter()
+                .map(|key| type_key(&mut prompt, key.clone()))
+                .collect();
+            inputs
+        };
+
+        assert_eq!(
+            type_keys(&[Key::Char('q')]),
+            [Some(Input::Key(Key::Char('q')))]
+        );
+        let inputs = type_keys(&[
+            Key::Char(':'),
+            Key::Char('q'),
+            Key::Char('x'),
+            Key::Backspace,
+        ]);
+        assert_eq!(inputs[1], Some(Input::Prompt(Some("q".into()))));
+        assert_eq!(inputs[3], Some(Input::Prompt(Some("q".into()))));
+        assert_eq!(type_keys(&[Key::ArrowUp]), [None]);
+        assert_eq!(type_keys(&[Key::Enter]), [Some(Input::Command("q".into()))]);
+        assert_eq!(
+            type_keys(&[Key::Char('h')]),
+            [Some(Input::Key(Key::Char('h')))]
+        );
+        let inputs = type_keys(&[Key::Char(':'), Key::Escape, Key::Char('n')]);
+        assert_eq!(inputs[1], Some(Input::Prompt(None)));
+        assert_eq!(inputs[2], Some(Input::Key(Key::Char('n'))));
+    }
+}
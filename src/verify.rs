@@ -1,223 +1,202 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
-use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::env;
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{CompiledExercise, Exercise, ExerciseOutput, Mode, State};
+use crate::reporter::{report, Event, Step};
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// On failure, the exercise is returned with its compiler diagnostics, if any.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
     verbose: bool,
     success_hints: bool,
-) -> Result<(), &'a Exercise> {
-    let (num_done, total) = progress;
-    let bar = ProgressBar::new(total as u64);
-    let mut percentage = num_done as f32 / total as f32 * 100.0;
-    bar.set_style(ProgressStyle::default_bar()
-        .template("Progress: [{bar:60.green/red}] {pos}/{len} {msg}")
-        .progress_chars("#>-")
-    );
-    bar.set_position(num_done as u64);
-    bar.set_message(format!("({:.1} %)", percentage));
+) -> Result<(), (&'a Exercise, Vec<Diagnostic>)> {
+    let (mut done, total) = progress;
+    report(Event::Progress { done, total });
 
     for exercise in exercises {
         let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
+            Mode::Test | Mode::Doctest | Mode::Bench => {
+                compile_and_test(exercise, RunMode::Interactive, verbose, success_hints)
+            }
+            Mode::Compile | Mode::Output => compile_and_run_interactively(exercise, success_hints),
+            Mode::Clippy | Mode::CompileFail => compile_only(exercise, success_hints),
             Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
 
         };
-        if !compile_result.unwrap_or(false) {
-            return Err(exercise);
+        match compile_result {
+            Ok(true) => {}
+            Ok(false) => return Err((exercise, Vec::new())),
+            Err(diagnostics) => return Err((exercise, diagnostics)),
         }
-        percentage += 100.0 / total as f32;
-        bar.inc(1);
-        bar.set_message(format!("({:.1} %)", percentage));
+        done += 1;
+        report(Event::Progress { done, total });
     }
     Ok(())
 }
 
+// The result of checking a single exercise without printing anything,
+// for front ends that present the output themselves
+pub enum Check {
+    // The exercise works and no longer has its `I AM NOT DONE` comment
+    Done,
+    // The exercise works, but is still marked as not done.
+    // The output of the binary or test harness is attached, if there is one.
+    Pending(Option<String>),
+    // The exercise failed to compile, or running it failed
+    Failed(ExerciseOutput),
+}
+
+pub fn check(exercise: &Exercise, verbose: bool) -> Check {
+    let compilation = match exercise.compile() {
+        Ok(compilation) => compilation,
+        Err(output) => return Check::Failed(output),
+    };
+    let output = match exercise.mode {
+        Mode::Clippy | Mode::CompileFail => None,
+        _ => match compilation.run() {
+            Ok(output) if matches!(exercise.mode, Mode::Compile | Mode::Output) || verbose => {
+                Some(output.stdout)
+            }
+            Ok(_) => None,
+            Err(output) => return Check::Failed(output),
+        },
+    };
+    match exercise.state() {
+        State::Done => Check::Done,
+        State::Pending(_) => Check::Pending(output),
+    }
+}
+
 enum RunMode {
     Interactive,
     NonInteractive,
 }
 
-// Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+// Compile and run the resulting test harness of the given Exercise.
+// A compilation failure carries the compiler diagnostics.
+pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), Vec<Diagnostic>> {
     compile_and_test(exercise, RunMode::NonInteractive, verbose, false)?;
     Ok(())
 }
 
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
-
-    let _ = compile(exercise, &progress_bar)?;
-    progress_bar.finish_and_clear();
+fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, Vec<Diagnostic>> {
+    report(Event::Started {
+        exercise,
+        step: Step::Compile,
+    });
+    let _ = compile(exercise)?;
 
     Ok(prompt_for_completion(exercise, None, success_hints))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Compiling {exercise}..."));
-    progress_bar.enable_steady_tick(100);
-
-    let compilation = compile(exercise, &progress_bar)?;
-
-    progress_bar.set_message(format!("Running {exercise}..."));
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
-
-    let output = match result {
+fn compile_and_run_interactively(
+    exercise: &Exercise,
+    success_hints: bool,
+) -> Result<bool, Vec<Diagnostic>> {
+    report(Event::Started {
+        exercise,
+        step: Step::Compile,
+    });
+    let compilation = compile(exercise)?;
+
+    report(Event::Started {
+        exercise,
+        step: Step::Run,
+    });
+    let output = match compilation.run() {
         Ok(output) => output,
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
-            println!("{}", output.stdout);
-            println!("{}", output.stderr);
-            return Err(());
+            report(Event::Failed {
+                exercise,
+                step: Step::Run,
+                output: &output,
+            });
+            return Err(Vec::new());
         }
     };
 
-    Ok(prompt_for_completion(exercise, Some(output.stdout), success_hints))
+    Ok(prompt_for_completion(exercise, Some(&output.stdout), success_hints))
 }
 
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
-    let progress_bar = ProgressBar::new_spinner();
-    progress_bar.set_message(format!("Testing {exercise}..."));
-    progress_bar.enable_steady_tick(100);
-
-    let compilation = compile(exercise, &progress_bar)?;
-    let result = compilation.run();
-    progress_bar.finish_and_clear();
-
-    match result {
+fn compile_and_test(
+    exercise: &Exercise,
+    run_mode: RunMode,
+    verbose: bool,
+    success_hints: bool,
+) -> Result<bool, Vec<Diagnostic>> {
+    report(Event::Started {
+        exercise,
+        step: Step::Test,
+    });
+    let compilation = compile(exercise)?;
+
+    match compilation.run() {
         Ok(output) => {
             if verbose {
-                println!("{}", output.stdout);
+                report(Event::Output {
+                    exercise,
+                    output: &output.stdout,
+                });
             }
             if let RunMode::Interactive = run_mode {
                 Ok(prompt_for_completion(exercise, None, success_hints))
             } else {
+                report(Event::Passed { exercise });
                 Ok(true)
             }
         }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stdout);
-            Err(())
+            report(Event::Failed {
+                exercise,
+                step: Step::Test,
+                output: &output,
+            });
+            Err(Vec::new())
         }
     }
 }
 
 // Compile the given Exercise and return an object with information
 // about the state of the compilation
-fn compile<'a, 'b>(
-    exercise: &'a Exercise,
-    progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
-    let compilation_result = exercise.compile();
-
-    match compilation_result {
-        Ok(compilation) => Ok(compilation),
+pub fn compile(exercise: &Exercise) -> Result<CompiledExercise<'_>, Vec<Diagnostic>> {
+    match exercise.compile() {
+        Ok(compilation) => {
+            report(Event::Compiled { exercise });
+            Ok(compilation)
+        }
         Err(output) => {
-            progress_bar.finish_and_clear();
-            warn!(
-                "Compiling of {} failed! Please try again. Here's the output:",
-                exercise
-            );
-            println!("{}", output.stderr);
-            Err(())
+            report(Event::Failed {
+                exercise,
+                step: Step::Compile,
+                output: &output,
+            });
+            Err(output.diagnostics)
         }
     }
 }
 
-fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<String>, success_hints: bool) -> bool {
-    let context = match exercise.state() {
-        State::Done => return true,
-        State::Pending(context) => context,
-    };
-    match exercise.mode {
-        Mode::Compile => success!("Successfully ran {}!", exercise),
-        Mode::Test => success!("Successfully tested {}!", exercise),
-        Mode::Clippy => success!("Successfully compiled {}!", exercise),
-        Mode::BuildScript => success!("Successfully compiled {}!", exercise),
-    }
-
-    let no_emoji = env::var("NO_EMOJI").is_ok();
-
-    let clippy_success_msg = if no_emoji {
-        "The code is compiling, and Clippy is happy!"
-    } else {
-        "The code is compiling, and 📎 Clippy 📎 is happy!"
-    };
-
-    let success_msg = match exercise.mode {
-        Mode::Compile => "The code is compiling!",
-        Mode::Test => "The code is compiling, and the tests pass!",
-        Mode::Clippy => clippy_success_msg,
-        Mode::BuildScript => "Build script works!",
-    };
-    println!();
-    if no_emoji {
-        println!("~*~ {success_msg} ~*~")
-    } else {
-        println!("🎉 🎉  {success_msg} 🎉 🎉")
-    }
-    println!();
-
-    if let Some(output) = prompt_output {
-        println!("Output:");
-        println!("{}", separator());
-        println!("{output}");
-        println!("{}", separator());
-        println!();
-    }
-    if success_hints {
-        println!("Hints:");
-        println!("{}", separator());
-        println!("{}", exercise.hint);
-        println!("{}", separator());
-        println!();
-    }
-
-    println!("You can keep working on this exercise,");
-    println!(
-        "or jump into the next one by removing the {} comment:",
-        style("`I AM NOT DONE`").bold()
-    );
-    println!();
-    for context_line in context {
-        let formatted_line = if context_line.important {
-            format!("{}", style(context_line.line).bold())
-        } else {
-            context_line.line.to_string()
-        };
-
-        println!(
-            "{:>2} {}  {}",
-            style(context_line.number).blue().bold(),
-            style("|").blue(),
-            formatted_line
-        );
+fn prompt_for_completion(exercise: &Exercise, prompt_output: Option<&str>, success_hints: bool) -> bool {
+    match exercise.state() {
+        State::Done => {
+            report(Event::Done { exercise });
+            true
+        }
+        State::Pending(context) => {
+            report(Event::Pending {
+                exercise,
+                output: prompt_output,
+                hint: success_hints.then_some(exercise.hint.as_str()),
+                context: &context,
+            });
+            false
+        }
     }
-
-    false
-}
-
-fn separator() -> console::StyledObject<&'static str> {
-    style("====================").bold()
 }
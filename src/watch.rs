@@ -0,0 +1,1162 @@
+use crate::config;
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{self, Exercise, ExerciseList, INFO_TOML_PATH};
+use crate::reporter;
+use crate::run::{reset, run};
+use crate::toolchain;
+use crate::tui;
+use crate::verify::verify;
+use crate::worker::Job;
+use console::Term;
+use notify::DebouncedEvent;
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// How long file events are collected before being reported
+const DEBOUNCE_DELAY: Duration = Duration::from_secs(1);
+// How often the file system is scanned when polling for changes
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// How often the watch loop checks for commands typed in the shell
+const SHELL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub enum WatchStatus {
+    Finished,
+    Unfinished,
+}
+
+// Keeps the watcher in use alive for as long as watch mode runs.
+// The watchers are never read, events arrive through the channel.
+#[allow(dead_code)]
+enum FileWatcher {
+    Native(RecommendedWatcher),
+    Poll(PollWatcher),
+}
+
+fn watch_paths<W: Watcher>(mut watcher: W) -> notify::Result<W> {
+    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
+    // info.toml is watched through its directory, as editors often
+    // replace the file instead of writing to it
+    watcher.watch(Path::new("."), RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+// The interval to poll the file system with, from `--poll` and `--poll-interval`.
+// Without either, OS notifications are used instead of polling.
+pub fn poll_interval(poll: bool, interval_ms: Option<u64>) -> Result<Option<Duration>, String> {
+    match (poll, interval_ms) {
+        // Polling without pause would keep a CPU core busy
+        (_, Some(0)) => Err(tr!("watch.zero_poll_interval").to_string()),
+        (_, Some(ms)) => Ok(Some(Duration::from_millis(ms))),
+        (true, None) => Ok(Some(DEFAULT_POLL_INTERVAL)),
+        (false, None) => Ok(None),
+    }
+}
+
+// Start watching for changes with OS notifications (like inotify).
+// These are unreliable or unavailable on network file systems, in containers
+// and on WSL-mounted directories, so fall back to polling if they can't be set up.
+fn start_watcher(
+    tx: Sender<DebouncedEvent>,
+    poll_interval: Option<Duration>,
+) -> notify::Result<FileWatcher> {
+    if let Some(interval) = poll_interval {
+        return Ok(FileWatcher::Poll(watch_paths(PollWatcher::new(
+            tx, interval,
+        )?)?));
+    }
+
+    match RecommendedWatcher::new(tx.clone(), DEBOUNCE_DELAY).and_then(watch_paths) {
+        Ok(watcher) => Ok(FileWatcher::Native(watcher)),
+        Err(e) => {
+            reporter::warning(&tr!("watch.no_notifications", e));
+            println!("{}", tr!("watch.polling_fallback"));
+            Ok(FileWatcher::Poll(watch_paths(PollWatcher::new(
+                tx,
+                DEFAULT_POLL_INTERVAL,
+            )?)?))
+        }
+    }
+}
+
+// Commands typed in the watch shell that need the exercise list,
+// so they are handled by the watch loop
+#[derive(Debug)]
+pub enum ShellCommand {
+    List,
+    Next,
+    Run(String),
+    Reset(Option<String>),
+    Skip,
+    VerifyAll,
+    Explain,
+    Edit(Option<String>),
+}
+
+// The command typed in the watch shell, or at the `:` prompt of the
+// full-screen interface, if it is one that needs the exercise list
+pub fn parse_command(input: &str) -> Option<ShellCommand> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words.as_slice() {
+        ["list"] => Some(ShellCommand::List),
+        ["next"] => Some(ShellCommand::Next),
+        ["run", name] => Some(ShellCommand::Run(name.to_string())),
+        ["reset"] => Some(ShellCommand::Reset(None)),
+        ["reset", name] => Some(ShellCommand::Reset(Some(name.to_string()))),
+        ["skip"] => Some(ShellCommand::Skip),
+        ["verify", "all"] => Some(ShellCommand::VerifyAll),
+        ["explain"] => Some(ShellCommand::Explain),
+        ["edit"] => Some(ShellCommand::Edit(None)),
+        ["edit", name] => Some(ShellCommand::Edit(Some(name.to_string()))),
+        _ => None,
+    }
+}
+
+// The command of `!<cmd>`, split into words like a shell would
+pub fn shell_command(cmd: &str) -> Result<Command, String> {
+    let parts = split_shell_words(cmd).map_err(|e| tr!("watch.command_unparsable", cmd, e))?;
+    let (program, args) = parts.split_first().ok_or(tr!("watch.no_command"))?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+// Split a command line into words like a POSIX shell does,
+// honoring single quotes, double quotes and backslash escapes
+fn split_shell_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(word) = word.take() {
+                    words.push(word);
+                }
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(tr!("watch.missing_quote", "'")),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(tr!("watch.missing_quote", "\"")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(tr!("watch.missing_quote", "\"")),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(word) = word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+// Open an exercise file with the editor of the configuration. It runs next to
+// watch mode, which keeps its output going, so graphical editors work best.
+pub fn open_in_editor(path: &Path) -> Result<(), String> {
+    let editor = config::get()
+        .editor
+        .as_deref()
+        .ok_or(tr!("watch.no_editor"))?;
+    let words = split_shell_words(editor)?;
+    let (program, args) = words
+        .split_first()
+        .ok_or(tr!("watch.empty_editor"))?;
+    Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+        .map_err(|e| tr!("watch.editor_failed", editor, e))
+}
+
+fn print_shell_help() {
+    println!("{}", tr!("watch.help"));
+}
+
+fn spawn_watch_shell(
+    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
+    should_quit: Arc<AtomicBool>,
+    commands: Sender<ShellCommand>,
+) {
+    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
+    println!("{}", tr!("watch.welcome"));
+    thread::spawn(move || loop {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) => break,
+            Ok(_) => {
+                let input = input.trim();
+                if let Some(command) = parse_command(input) {
+                    if commands.send(command).is_err() {
+                        break;
+                    }
+                } else if input == "hint" {
+                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
+                        println!("{hint}");
+                    }
+                } else if input == "clear" {
+                    println!("\x1B[2J\x1B[1;1H");
+                } else if input.eq("quit") {
+                    should_quit.store(true, Ordering::SeqCst);
+                    println!("{}", tr!("watch.bye"));
+                } else if input.eq("help") {
+                    print_shell_help();
+                } else if let Some(cmd) = input.strip_prefix('!') {
+                    match shell_command(cmd) {
+                        Ok(mut command) => {
+                            if let Err(e) = command.status() {
+                                println!("{}", tr!("watch.command_failed", cmd, e));
+                            }
+                        }
+                        Err(e) => println!("{e}"),
+                    }
+                } else if input.split_whitespace().next() == Some("run") {
+                    println!("{}", tr!("watch.run_usage"));
+                } else {
+                    println!("{}", tr!("watch.unknown_command", input));
+                }
+            }
+            Err(error) => println!("{}", tr!("watch.read_error", error)),
+        }
+    });
+}
+
+/* Clears the terminal with an ANSI escape code.
+Works in UNIX and newer Windows terminals. */
+fn clear_screen() {
+    println!("\x1Bc");
+}
+
+// A file system event, reduced to what watch mode cares about
+#[derive(Debug, PartialEq)]
+pub enum FileEvent {
+    // The file was created, written to, or moved into place
+    Changed(PathBuf),
+    // The file no longer exists
+    Removed(PathBuf),
+    // Events may have been missed, so everything should be looked at again
+    Rescan,
+}
+
+// Normalize notify's events. Editors saving atomically write to a temporary
+// file and rename it over the original, and some move the original away first,
+// so whether a path counts as removed is decided by checking the file system.
+pub fn normalize(event: DebouncedEvent) -> Vec<FileEvent> {
+    let paths = match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Chmod(path)
+        | DebouncedEvent::Remove(path) => vec![path],
+        DebouncedEvent::Rename(from, to) => vec![from, to],
+        DebouncedEvent::Rescan => return vec![FileEvent::Rescan],
+        DebouncedEvent::Error(e, path) => {
+            println!("{}", tr!("watch.error", format!("{e:?} ({path:?})")));
+            return Vec::new();
+        }
+        DebouncedEvent::NoticeWrite(_) | DebouncedEvent::NoticeRemove(_) => return Vec::new(),
+    };
+    paths
+        .into_iter()
+        .filter(|path| !is_temp_file(path))
+        .map(|path| {
+            if path.exists() {
+                FileEvent::Changed(path)
+            } else {
+                FileEvent::Removed(path)
+            }
+        })
+        .collect()
+}
+
+// Swap, backup and lock files created by editors while saving
+fn is_temp_file(path: &Path) -> bool {
+    let name = match path.file_name().and_then(OsStr::to_str) {
+        Some(name) => name,
+        None => return false,
+    };
+    name.ends_with('~')
+        || name.starts_with(".#")
+        || (name.starts_with('#') && name.ends_with('#'))
+        || name.contains("___jb_tmp___")
+        || name.contains("___jb_old___")
+        || [".swp", ".swo", ".swx", ".tmp", ".bak"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+        || name == "4913"
+}
+
+// Make a path absolute so it can be compared with the exercise paths.
+// Removed files can't be canonicalized, but their directory usually can.
+pub fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .ok()
+        .or_else(|| {
+            let parent = path.parent()?.canonicalize().ok()?;
+            Some(parent.join(path.file_name()?))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+// What a changed file means for watch mode
+pub enum Change {
+    // An exercise source file, the expected output of one or an
+    // exercise-local Cargo manifest was edited
+    Exercise,
+    // The exercise list itself was edited
+    ExerciseList,
+    // Anything else, like build artifacts or manifests rustlings generates
+    Irrelevant,
+}
+
+pub fn classify(path: &Path, info_toml: &Path) -> Change {
+    if path == info_toml {
+        Change::ExerciseList
+    } else if path.starts_with(info_toml.with_file_name("exercises"))
+        && (path.extension() == Some(OsStr::new("rs"))
+            || path.extension() == Some(OsStr::new("expected"))
+            || (path.file_name() == Some(OsStr::new("Cargo.toml"))
+                && !exercise::is_generated_manifest(path)))
+    {
+        Change::Exercise
+    } else {
+        Change::Irrelevant
+    }
+}
+
+// The code of the first error `rustc --explain` knows about. Lints, even
+// when denied, have no explanation there.
+pub fn explainable_code(diagnostics: &[Diagnostic]) -> Option<String> {
+    diagnostics
+        .iter()
+        .filter(|d| d.is_error() && !d.is_lint())
+        .find_map(|d| d.code.clone())
+}
+
+// Watch mode pinned to a single exercise or to a topic with `--only`,
+// where a topic is a directory of exercises like `smart_pointers`
+#[derive(Clone)]
+pub struct Focus {
+    only: String,
+}
+
+impl Focus {
+    pub fn new(only: &str, exercises: &[Exercise]) -> Result<Focus, String> {
+        let focus = Focus {
+            only: only.to_string(),
+        };
+        if exercises.iter().any(|e| focus.contains(e)) {
+            Ok(focus)
+        } else {
+            Err(tr!("watch.no_focus", only))
+        }
+    }
+
+    pub fn contains(&self, exercise: &Exercise) -> bool {
+        exercise.name == self.only
+            || exercise.path.parent().and_then(Path::file_name) == Some(OsStr::new(&self.only))
+    }
+
+    // The focused exercise at `path`, if the path belongs to one
+    pub fn find<'a>(&self, exercises: &'a [Exercise], path: &Path) -> Option<&'a Exercise> {
+        exercises
+            .iter()
+            .find(|e| path.ends_with(&e.path) && self.contains(e))
+    }
+
+    pub fn exercises<'a>(&self, exercises: &'a [Exercise]) -> Vec<&'a Exercise> {
+        exercises.iter().filter(|e| self.contains(e)).collect()
+    }
+
+    // The exercise to start with: the first one that is not done yet
+    pub fn first<'a>(&self, exercises: &'a [Exercise]) -> Option<&'a Exercise> {
+        let focused = self.exercises(exercises);
+        focused
+            .iter()
+            .find(|e| e.path.exists() && !e.looks_done())
+            .or_else(|| focused.first())
+            .copied()
+    }
+
+    // The exercise the learner moves on to after `current`
+    pub fn after<'a>(&self, exercises: &'a [Exercise], current: &Path) -> Option<&'a Exercise> {
+        self.exercises(exercises)
+            .into_iter()
+            .skip_while(|e| e.path != current)
+            .nth(1)
+    }
+
+    pub fn progress(&self, exercises: &[Exercise]) -> (usize, usize) {
+        let focused = self.exercises(exercises);
+        let num_done = focused
+            .iter()
+            .filter(|e| e.path.exists() && e.looks_done())
+            .count();
+        (num_done, focused.len())
+    }
+}
+
+impl Display for Focus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.only)
+    }
+}
+
+// The exercises watch mode goes through and the one it is on. Both the
+// line-based shell and the full-screen interface pick exercises through it.
+pub struct Selection {
+    pub exercises: Vec<Exercise>,
+    // The exercise or topic watch mode is pinned to, if any
+    pub focus: Option<Focus>,
+    // Exercises the learner skipped during this session
+    pub skipped: HashSet<PathBuf>,
+    // The exercise checking last stopped at
+    pub current_exercise: Option<PathBuf>,
+}
+
+impl Selection {
+    pub fn new(exercises: Vec<Exercise>, focus: Option<Focus>) -> Selection {
+        Selection {
+            exercises,
+            focus,
+            skipped: HashSet::new(),
+            current_exercise: None,
+        }
+    }
+
+    pub fn current(&self) -> Option<&Exercise> {
+        let current = self.current_exercise.as_ref()?;
+        self.exercises.iter().find(|e| &e.path == current)
+    }
+
+    // Whether changes to the file at `path` are ignored, because watch mode
+    // is pinned to other exercises
+    pub fn ignores(&self, path: &Path) -> bool {
+        self.focus
+            .as_ref()
+            .is_some_and(|focus| focus.find(&self.exercises, path).is_none())
+    }
+
+    // The exercises done and the total, of the focus if there is one
+    pub fn progress(&self) -> (usize, usize) {
+        match &self.focus {
+            Some(focus) => focus.progress(&self.exercises),
+            None => {
+                let num_done = self
+                    .exercises
+                    .iter()
+                    .filter(|e| e.path.exists() && e.looks_done())
+                    .count();
+                (num_done, self.exercises.len())
+            }
+        }
+    }
+
+    // With `--only`, make the edited exercise at `changed` the current one,
+    // or the first one not done yet if the current one is gone. Returns
+    // whether there is a focus, otherwise all pending exercises are checked.
+    pub fn refocus(&mut self, changed: Option<&Path>) -> bool {
+        let Some(focus) = &self.focus else {
+            return false;
+        };
+        if let Some(exercise) = changed.and_then(|path| focus.find(&self.exercises, path)) {
+            self.current_exercise = Some(exercise.path.clone());
+        } else if self.current().is_none() {
+            self.current_exercise = focus.first(&self.exercises).map(|e| e.path.clone());
+        }
+        true
+    }
+
+    // The current exercise of the focus, to check whether solved or not
+    pub fn focused(&self) -> Option<Exercise> {
+        self.focus.as_ref()?;
+        self.current().cloned()
+    }
+
+    // Move on to the next exercise of the focus, or tell why there is none
+    pub fn move_on(&mut self) -> Result<(), String> {
+        let (Some(focus), Some(current)) = (&self.focus, &self.current_exercise) else {
+            return Err(tr!("watch.nothing_next").to_string());
+        };
+        let next = focus
+            .after(&self.exercises, current)
+            .ok_or_else(|| tr!("watch.last_of_focus", focus))?;
+        self.current_exercise = Some(next.path.clone());
+        Ok(())
+    }
+
+    // All exercises with their status, one per line, the current one marked
+    pub fn list(&self) -> String {
+        let mut list = String::new();
+        for exercise in &self.exercises {
+            let status = if self.skipped.contains(&exercise.path) {
+                tr!("list.skipped")
+            } else if exercise.path.exists() && exercise.looks_done() {
+                tr!("list.done")
+            } else {
+                tr!("list.pending")
+            };
+            let marker = if self.current_exercise.as_ref() == Some(&exercise.path) {
+                ">"
+            } else {
+                " "
+            };
+            list.push_str(&format!(
+                "{marker} {:<17}\t{:<46}\t{status:<7}\n",
+                exercise.name,
+                exercise.path.display()
+            ));
+        }
+        list
+    }
+
+    // Leave the current exercise for later
+    pub fn skip(&mut self) -> Result<(), String> {
+        let path = self
+            .current_exercise
+            .take()
+            .ok_or_else(|| tr!("watch.nothing_to_skip").to_string())?;
+        self.skipped.insert(path);
+        Ok(())
+    }
+}
+
+// The exercises to check in order: the one at `changed` if any, then the
+// others that are not done yet, leaving out the skipped ones
+pub fn pending<'a>(
+    exercises: &'a [Exercise],
+    changed: Option<&Path>,
+    skipped: &HashSet<PathBuf>,
+) -> Vec<&'a Exercise> {
+    let edited = changed.and_then(|path| exercises.iter().find(|e| path.ends_with(&e.path)));
+    edited
+        .into_iter()
+        .chain(exercises.iter().filter(|e| {
+            e.path.exists()
+                && !skipped.contains(&e.path)
+                && !e.looks_done()
+                && edited.map(|e| &e.path) != Some(&e.path)
+        }))
+        .collect()
+}
+
+// The exercise verification stopped at, detached from the exercise list
+struct Failure {
+    path: PathBuf,
+    hint: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl From<(&Exercise, Vec<Diagnostic>)> for Failure {
+    fn from((exercise, diagnostics): (&Exercise, Vec<Diagnostic>)) -> Failure {
+        Failure {
+            path: exercise.path.clone(),
+            hint: exercise.hint.to_owned(),
+            diagnostics,
+        }
+    }
+}
+
+// What a background job of the watch loop found out
+enum Outcome {
+    // Verification stopped at a failure, or got through all exercises
+    Verified(Option<Failure>),
+    // An exercise was run on request, which leaves the current exercise alone
+    Ran,
+}
+
+// Everything the watch loop keeps track of between file changes and commands
+struct WatchState {
+    selection: Selection,
+    verbose: bool,
+    success_hints: bool,
+    // The error code of the last compilation failure, for `explain`
+    last_error_code: Option<String>,
+    failed_exercise_hint: Arc<Mutex<Option<String>>>,
+    // Verification or a run in progress, so the loop stays responsive
+    job: Option<Job<Outcome>>,
+}
+
+impl WatchState {
+    // Run `work` on a copy of the exercise list in the background.
+    // Whatever was still running is stale by now and gets cancelled.
+    fn start(&mut self, work: impl FnOnce(&[Exercise]) -> Outcome + Send + 'static) {
+        self.cancel();
+        let exercises = self.selection.exercises.clone();
+        self.job = Some(Job::spawn(move || work(&exercises)));
+    }
+
+    fn cancel(&mut self) {
+        if let Some(job) = self.job.take() {
+            job.cancel();
+        }
+    }
+
+    // Cancel the running job, then clear the screen so that its
+    // output doesn't mix with the output of what comes next
+    fn interrupt(&mut self) {
+        self.cancel();
+        clear_screen();
+    }
+
+    // Pick up the outcome of the background job once it is done.
+    // Returns whether all exercises are done.
+    fn poll_job(&mut self) -> bool {
+        if !self.job.as_ref().is_some_and(Job::is_finished) {
+            return false;
+        }
+        match self.job.take().and_then(Job::join) {
+            Some(Outcome::Verified(failure)) => self.record(failure),
+            Some(Outcome::Ran) | None => false,
+        }
+    }
+
+    // Verify the pending exercises, starting with the one at `changed` if any
+    fn verify_pending(&mut self, changed: Option<&Path>) {
+        let changed = changed.map(Path::to_path_buf);
+        let skipped = self.selection.skipped.clone();
+        let progress = self.selection.progress();
+        let (verbose, success_hints) = (self.verbose, self.success_hints);
+        self.start(move |exercises| {
+            let pending_exercises = pending(exercises, changed.as_deref(), &skipped);
+            let result = verify(pending_exercises, progress, verbose, success_hints);
+            Outcome::Verified(result.err().map(Failure::from))
+        });
+    }
+
+    // Verify again after the file at `changed` was edited, or after anything
+    // else may have changed. With `--only`, the current exercise of the focus
+    // is verified instead of all pending exercises.
+    fn reverify(&mut self, changed: Option<&Path>) {
+        if self.selection.refocus(changed) {
+            self.verify_focused();
+        } else {
+            self.verify_pending(changed);
+        }
+    }
+
+    // Verify the current exercise of the focus, whether solved or not
+    fn verify_focused(&mut self) {
+        let Some(exercise) = self.selection.focused() else {
+            return;
+        };
+        let progress = self.selection.progress();
+        let (verbose, success_hints) = (self.verbose, self.success_hints);
+        self.start(move |_| {
+            let result = verify([&exercise], progress, verbose, success_hints);
+            Outcome::Verified(result.err().map(Failure::from))
+        });
+    }
+
+    // Move on to the next exercise of the focus
+    fn move_on(&mut self) {
+        match self.selection.move_on() {
+            Ok(()) => {
+                self.interrupt();
+                self.verify_focused();
+            }
+            Err(message) => println!("{message}"),
+        }
+    }
+
+    // Verify all exercises from the start, whether solved or not.
+    // With `--only`, that's all exercises of the focus.
+    fn verify_all(&mut self) {
+        let (verbose, success_hints) = (self.verbose, self.success_hints);
+        let focus = self.selection.focus.clone();
+        self.start(move |exercises| {
+            let focused: Vec<&Exercise> = exercises
+                .iter()
+                .filter(|e| focus.as_ref().map_or(true, |f| f.contains(e)))
+                .collect();
+            let total = focused.len();
+            let result = verify(
+                focused.into_iter().filter(|e| e.path.exists()),
+                (0, total),
+                verbose,
+                success_hints,
+            );
+            Outcome::Verified(result.err().map(Failure::from))
+        });
+    }
+
+    fn run(&mut self, exercise: Exercise) {
+        let verbose = self.verbose;
+        self.start(move |_| {
+            let _ = run(&exercise, verbose);
+            Outcome::Ran
+        });
+    }
+
+    fn record(&mut self, failure: Option<Failure>) -> bool {
+        match failure {
+            None if self.selection.focus.is_some() => self.print_focus_done(),
+            None if self.selection.skipped.is_empty() => return true,
+            None => {
+                self.selection.current_exercise = None;
+                reporter::success(&tr!("watch.skipped_done", self.selection.skipped.len()));
+                println!("{}", tr!("watch.restart_for_skipped"));
+            }
+            Some(failure) => {
+                self.selection.current_exercise = Some(failure.path);
+                *self.failed_exercise_hint.lock().unwrap() = Some(failure.hint);
+                if let Some(code) = explainable_code(&failure.diagnostics) {
+                    self.last_error_code = Some(code);
+                }
+            }
+        }
+        false
+    }
+
+    // With `--only`, watch mode stays on a solved exercise until the learner moves on
+    fn print_focus_done(&self) {
+        let selection = &self.selection;
+        let (focus, current) = match (&selection.focus, selection.current()) {
+            (Some(focus), Some(current)) => (focus, current),
+            _ => return,
+        };
+        reporter::success(&tr!("watch.exercise_done", current));
+        match focus.after(&selection.exercises, &current.path) {
+            Some(next) => println!("{}", tr!("watch.type_next", next)),
+            None => println!("{}", tr!("watch.focus_done", focus)),
+        }
+    }
+
+    fn find(&self, name: &str) -> Option<&Exercise> {
+        let exercise = self.selection.exercises.iter().find(|e| e.name == name);
+        if exercise.is_none() {
+            println!("{}", tr!("main.no_exercise", name));
+        }
+        exercise
+    }
+
+    fn handle_file_event(&mut self, event: FileEvent, info_toml: &Path) {
+        match event {
+            FileEvent::Changed(path) => {
+                let filepath = absolute(&path);
+                match classify(&filepath, info_toml) {
+                    Change::Exercise if !self.selection.ignores(&filepath) => {
+                        self.interrupt();
+                        self.reverify(Some(&filepath))
+                    }
+                    Change::ExerciseList => {
+                        self.interrupt();
+                        match ExerciseList::from_file(INFO_TOML_PATH) {
+                            Ok(list) => {
+                                self.selection.exercises = list.exercises;
+                                reporter::success(&tr!("watch.reloaded", INFO_TOML_PATH));
+                                self.reverify(None)
+                            }
+                            Err(e) => {
+                                reporter::warning(&tr!("watch.reload_failed", e));
+                                println!("{}", tr!("watch.keeps_previous_list"));
+                            }
+                        }
+                    }
+                    Change::Exercise | Change::Irrelevant => {}
+                }
+            }
+            FileEvent::Removed(path) => {
+                let filepath = absolute(&path);
+                let exercises = &self.selection.exercises;
+                if let Some(exercise) = exercises.iter().find(|e| filepath.ends_with(&e.path)) {
+                    if self.selection.current_exercise.as_ref() == Some(&exercise.path) {
+                        reporter::warning(&tr!("watch.current_removed", exercise));
+                    } else {
+                        reporter::warning(&tr!("watch.exercise_removed", exercise));
+                    }
+                    println!("{}", tr!("watch.restore_with", exercise.name));
+                }
+            }
+            FileEvent::Rescan => {
+                self.interrupt();
+                self.reverify(None)
+            }
+        }
+    }
+
+    fn handle_command(&mut self, command: ShellCommand) {
+        match command {
+            ShellCommand::List => print!("{}", self.selection.list()),
+            ShellCommand::Next | ShellCommand::Skip if self.selection.focus.is_some() => {
+                self.move_on()
+            }
+            ShellCommand::Next => {
+                let selection = &self.selection;
+                match pending(&selection.exercises, None, &selection.skipped)
+                    .first()
+                    .map(|e| (*e).clone())
+                {
+                    Some(exercise) => self.run(exercise),
+                    None => println!("{}", tr!("watch.nothing_next")),
+                }
+            }
+            ShellCommand::Run(name) => {
+                if let Some(exercise) = self.find(&name).cloned() {
+                    self.run(exercise);
+                }
+            }
+            ShellCommand::Reset(name) => {
+                let exercise = match &name {
+                    Some(name) => self.find(name),
+                    None => self.selection.current(),
+                };
+                match exercise {
+                    Some(exercise) => {
+                        if reset(exercise).is_err() {
+                            reporter::warning(&tr!("watch.reset_failed", exercise));
+                        }
+                    }
+                    None => println!("{}", tr!("watch.nothing_to_reset")),
+                }
+            }
+            ShellCommand::Skip => match self.selection.skip() {
+                Ok(()) => {
+                    self.interrupt();
+                    self.verify_pending(None);
+                }
+                Err(message) => println!("{message}"),
+            },
+            ShellCommand::VerifyAll => {
+                self.interrupt();
+                self.verify_all();
+            }
+            ShellCommand::Explain => match &self.last_error_code {
+                Some(code) => {
+                    if let Err(e) = toolchain::rustc().args(["--explain", code]).status() {
+                        println!("{}", tr!("watch.explain_failed", code, e));
+                    }
+                }
+                None => println!("{}", tr!("watch.no_error_code")),
+            },
+            ShellCommand::Edit(name) => {
+                let exercise = match &name {
+                    Some(name) => self.find(name),
+                    None => self.selection.current(),
+                };
+                match exercise {
+                    Some(exercise) => {
+                        if let Err(e) = open_in_editor(&exercise.path) {
+                            println!("{e}");
+                        }
+                    }
+                    None => println!("{}", tr!("watch.nothing_to_edit")),
+                }
+            }
+        }
+    }
+}
+
+// Watch for changes and re-verify. The full-screen interface is used when
+// requested and stdout is a terminal, the line-based shell otherwise.
+pub fn watch(
+    exercises: Vec<Exercise>,
+    verbose: bool,
+    success_hints: bool,
+    poll_interval: Option<Duration>,
+    tui: bool,
+    focus: Option<Focus>,
+) -> notify::Result<WatchStatus> {
+    let (tx, rx) = channel();
+    let (command_tx, command_rx) = channel();
+    let should_quit = Arc::new(AtomicBool::new(false));
+
+    let _watcher = start_watcher(tx, poll_interval)?;
+    let info_toml = Path::new(INFO_TOML_PATH).canonicalize()?;
+
+    if tui && Term::stdout().is_term() {
+        return Ok(tui::watch(
+            exercises,
+            verbose,
+            success_hints,
+            rx,
+            &info_toml,
+            focus,
+        )?);
+    }
+
+    clear_screen();
+
+    let mut state = WatchState {
+        selection: Selection::new(exercises, focus),
+        verbose,
+        success_hints,
+        last_error_code: None,
+        failed_exercise_hint: Arc::new(Mutex::new(None)),
+        job: None,
+    };
+    if state.selection.focus.is_some() {
+        state.reverify(None);
+    } else {
+        state.verify_all();
+    }
+    // The shell is only started once the first verification is through,
+    // so there's no need for it if everything is done already
+    let mut shell_commands = Some(command_tx);
+    loop {
+        match rx.recv_timeout(SHELL_POLL_INTERVAL) {
+            Ok(event) => {
+                for event in normalize(event) {
+                    state.handle_file_event(event, &info_toml);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // the timeout expired, check for shell commands, the running
+                // verification and the `should_quit` variable
+            }
+            Err(e) => println!("{}", tr!("watch.error", format!("{e:?}"))),
+        }
+        for command in command_rx.try_iter() {
+            state.handle_command(command);
+        }
+        if state.poll_job() {
+            return Ok(WatchStatus::Finished);
+        }
+        if state.job.is_none() {
+            if let Some(commands) = shell_commands.take() {
+                spawn_watch_shell(
+                    &state.failed_exercise_hint,
+                    Arc::clone(&should_quit),
+                    commands,
+                );
+            }
+        }
+        // Check if we need to exit
+        if should_quit.load(Ordering::SeqCst) {
+            state.cancel();
+            return Ok(WatchStatus::Unfinished);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::diagnostics::Level;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_classify() {
+        let info_toml = Path::new("/rustlings/info.toml");
+        let classify = |path| classify(Path::new(path), info_toml);
+
+        assert!(matches!(classify("/rustlings/info.toml"), Change::ExerciseList));
+        assert!(matches!(
+            classify("/rustlings/exercises/intro/intro1.rs"),
+            Change::Exercise
+        ));
+        assert!(matches!(
+            classify("/rustlings/exercises/iterators/iterators6.expected"),
+            Change::Exercise
+        ));
+        assert!(matches!(
+            classify("/rustlings/exercises/tests/Cargo.toml"),
+            Change::Irrelevant
+        ));
+        assert!(matches!(
+            classify("/rustlings/exercises/clippy/Cargo.toml"),
+            Change::Irrelevant
+        ));
+        assert!(matches!(
+            classify("/rustlings/exercises/modules/Cargo.toml"),
+            Change::Exercise
+        ));
+        assert!(matches!(
+            classify("/rustlings/exercises/clippy/target/debug/clippy1"),
+            Change::Irrelevant
+        ));
+        assert!(matches!(classify("/rustlings/src/main.rs"), Change::Irrelevant));
+    }
+
+    #[test]
+    fn test_explainable_code() {
+        let diagnostic = |level, code: &str| Diagnostic {
+            level,
+            code: Some(code.to_string()),
+            message: String::new(),
+            primary_span: None,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            suggestions: Vec::new(),
+        };
+
+        let diagnostics = [
+            diagnostic(Level::Warning, "unused_variables"),
+            diagnostic(Level::Error, "clippy::len_zero"),
+            diagnostic(Level::Error, "E0382"),
+        ];
+        assert_eq!(explainable_code(&diagnostics), Some("E0382".to_string()));
+        assert_eq!(explainable_code(&diagnostics[..2]), None);
+    }
+
+    #[test]
+    fn test_normalize_atomic_save() {
+        let dir = Path::new("tests/fixture/state");
+        let exercise = dir.join("pending_exercise.rs");
+        let events = normalize(DebouncedEvent::Rename(
+            dir.join("pending_exercise.rs___jb_tmp___"),
+            exercise.clone(),
+        ));
+        assert_eq!(events, vec![FileEvent::Changed(exercise)]);
+
+        let events = normalize(DebouncedEvent::Rename(
+            dir.join("missing_exercise.rs"),
+            dir.join("missing_exercise.rs~"),
+        ));
+        assert_eq!(
+            events,
+            vec![FileEvent::Removed(dir.join("missing_exercise.rs"))]
+        );
+    }
+
+    #[test]
+    fn test_poll_interval() {
+        assert_eq!(poll_interval(false, None), Ok(None));
+        assert_eq!(poll_interval(true, None), Ok(Some(DEFAULT_POLL_INTERVAL)));
+        assert_eq!(
+            poll_interval(false, Some(250)),
+            Ok(Some(Duration::from_millis(250)))
+        );
+        assert_eq!(
+            poll_interval(true, Some(250)),
+            Ok(Some(Duration::from_millis(250)))
+        );
+        assert!(poll_interval(false, Some(0)).is_err());
+        assert!(poll_interval(true, Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_focus() {
+        let exercise = |name, path| Exercise::for_test(name, path, Mode::Compile);
+        let exercises = [
+            exercise("box1", "exercises/smart_pointers/box1.rs"),
+            exercise("rc1", "exercises/smart_pointers/rc1.rs"),
+            exercise("threads1", "exercises/threads/threads1.rs"),
+        ];
+
+        let topic = Focus::new("smart_pointers", &exercises).unwrap();
+        assert_eq!(topic.exercises(&exercises).len(), 2);
+        assert_eq!(
+            topic.after(&exercises, Path::new("exercises/smart_pointers/box1.rs")).map(|e| &e.name),
+            Some(&"rc1".to_string())
+        );
+        assert!(topic.after(&exercises, Path::new("exercises/smart_pointers/rc1.rs")).is_none());
+        assert!(topic
+            .find(&exercises, Path::new("/rustlings/exercises/threads/threads1.rs"))
+            .is_none());
+
+        let single = Focus::new("threads1", &exercises).unwrap();
+        assert_eq!(single.exercises(&exercises).len(), 1);
+        assert!(Focus::new("macros", &exercises).is_err());
+    }
+
+    #[test]
+    fn test_selection() {
+        let exercise = |name: &str| {
+            let path = format!("tests/fixture/state/{name}.rs");
+            Exercise::for_test(name, &path, Mode::Compile)
+        };
+        let exercises = vec![
+            exercise("pending_exercise"),
+            exercise("finished_exercise"),
+            exercise("pending_test_exercise"),
+        ];
+        let names = |exercises: Vec<&Exercise>| -> Vec<String> {
+            exercises.iter().map(|e| e.name.clone()).collect()
+        };
+
+        let edited = Path::new("/rustlings/tests/fixture/state/pending_test_exercise.rs");
+        let skipped = HashSet::new();
+        assert_eq!(
+            names(pending(&exercises, Some(edited), &skipped)),
+            ["pending_test_exercise", "pending_exercise"]
+        );
+
+        let mut selection = Selection::new(exercises, None);
+        assert_eq!(selection.progress(), (1, 3));
+        assert!(!selection.refocus(None));
+        assert!(selection.skip().is_err());
+        selection.current_exercise = Some(PathBuf::from("tests/fixture/state/pending_exercise.rs"));
+        assert!(selection.skip().is_ok());
+        assert_eq!(
+            names(pending(&selection.exercises, None, &selection.skipped)),
+            ["pending_test_exercise"]
+        );
+
+        let focus = Focus::new("state", &selection.exercises).ok();
+        let mut selection = Selection::new(selection.exercises, focus);
+        assert!(selection.refocus(None));
+        assert_eq!(selection.focused().unwrap().name, "pending_exercise");
+        assert!(selection.ignores(Path::new("/rustlings/exercises/intro/intro1.rs")));
+        assert!(selection.refocus(Some(edited)));
+        assert_eq!(selection.focused().unwrap().name, "pending_test_exercise");
+        assert!(selection.move_on().is_err());
+    }
+
+    #[test]
+    fn test_split_shell_words() {
+        assert_eq!(
+            split_shell_words("rustc --explain E0381").unwrap(),
+            vec!["rustc", "--explain", "E0381"]
+        );
+        assert_eq!(
+            split_shell_words(r#"echo "hello world" 'it''s' a\ b "q\"uote""#).unwrap(),
+            vec!["echo", "hello world", "its", "a b", "q\"uote"]
+        );
+        assert_eq!(split_shell_words("  ").unwrap(), Vec::<String>::new());
+        assert!(split_shell_words("echo 'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_is_temp_file() {
+        for name in [
+            "intro1.rs~",
+            ".intro1.rs.swp",
+            ".#intro1.rs",
+            "#intro1.rs#",
+            "intro1.rs___jb_old___",
+            "4913",
+        ] {
+            assert!(is_temp_file(Path::new(name)), "{name}");
+        }
+        assert!(!is_temp_file(Path::new("exercises/intro/intro1.rs")));
+    }
+}
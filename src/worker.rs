@@ -0,0 +1,302 @@
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, Output, Stdio};
+#[cfg(unix)]
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::Once;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// How often a running command checks whether its job was cancelled,
+// or whether it ran out of time
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// The process groups of the commands running right now, zero for a free slot.
+// Being in a group of their own, they don't get the SIGINT of Ctrl-C from the
+// terminal, so rustlings kills them before it goes down.
+#[cfg(unix)]
+#[allow(clippy::declare_interior_mutable_const)]
+const FREE: AtomicI32 = AtomicI32::new(0);
+#[cfg(unix)]
+static GROUPS: [AtomicI32; 64] = [FREE; 64];
+
+// Whether the full-screen interface of watch mode took over the terminal,
+// which has to be handed back when rustlings is interrupted
+#[cfg(unix)]
+static TERMINAL_TAKEN: AtomicBool = AtomicBool::new(false);
+// Show the cursor and clear the screen, like quitting the full-screen interface does
+#[cfg(unix)]
+const RESTORE_TERMINAL: &[u8] = b"\x1b[?25h\r\x1b[2J\r\x1b[H";
+
+thread_local! {
+    // The cancellation flag of the job running on this thread, if any
+    static CANCELLED: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+// Work running on a background thread, like verifying exercises in watch mode.
+// Cancelling a job kills the commands it started through `output`.
+pub struct Job<T> {
+    cancelled: Arc<AtomicBool>,
+    handle: JoinHandle<T>,
+}
+
+impl<T: Send + 'static> Job<T> {
+    pub fn spawn(work: impl FnOnce() -> T + Send + 'static) -> Job<T> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&cancelled);
+        let handle = thread::spawn(move || {
+            CANCELLED.with(|cancelled| *cancelled.borrow_mut() = Some(flag));
+            work()
+        });
+        Job { cancelled, handle }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    // Wait for the job to finish. Returns `None` if it panicked.
+    pub fn join(self) -> Option<T> {
+        self.handle.join().ok()
+    }
+
+    // Kill the commands the job is running and wait for it to wind down.
+    // Whatever it returns is stale and thrown away.
+    pub fn cancel(self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        let _ = self.handle.join();
+    }
+}
+
+// Run a command to completion and collect its output like `Command::output`.
+// On the thread of a job, the command and everything it started are killed
+// as soon as the job is cancelled, and the output up to then is returned.
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    output_with(command, None, None)
+}
+
+// Like `output`, but the command reads `input` from its standard input, if
+// given, and is also killed once it ran for longer than the timeout, failing
+// with `io::ErrorKind::TimedOut`
+pub fn output_with(
+    command: &mut Command,
+    input: Option<&[u8]>,
+    timeout: Option<Duration>,
+) -> io::Result<Output> {
+    let cancelled = CANCELLED.with(|cancelled| cancelled.borrow().clone());
+    if cancelled.is_none() && timeout.is_none() && input.is_none() {
+        return command.output();
+    }
+
+    command
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Cargo runs rustc and clippy-driver as children of its own. Put them all
+    // in a process group of their own, so they can be killed together.
+    #[cfg(unix)]
+    {
+        std::os::unix::process::CommandExt::process_group(command, 0);
+        handle_interrupts();
+    }
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+    let _group = Group::register(&child);
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        // Write on a separate thread, as the command may only read after
+        // writing output of its own. It may also never read its input, so
+        // failing to write isn't an error. The pipe closes once written.
+        let input = input.to_vec();
+        thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancelled.as_ref().is_some_and(|cancelled| cancelled.load(Ordering::SeqCst)) {
+            kill(&mut child);
+            break child.wait()?;
+        }
+        if let Some(timeout) = timeout.filter(|timeout| start.elapsed() >= *timeout) {
+            kill(&mut child);
+            child.wait()?;
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("killed after running for {}s", timeout.as_secs_f32()),
+            ));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+// Have the terminal restored if rustlings is interrupted while the full-screen
+// interface hides the cursor. Ctrl-C raises SIGINT while it waits for a key,
+// without the interface getting a chance to restore it.
+pub fn take_terminal(taken: bool) {
+    #[cfg(unix)]
+    {
+        TERMINAL_TAKEN.store(taken, Ordering::SeqCst);
+        handle_interrupts();
+    }
+    #[cfg(not(unix))]
+    let _ = taken;
+}
+
+#[cfg(unix)]
+fn handle_interrupts() {
+    static HANDLER: Once = Once::new();
+    HANDLER.call_once(|| unsafe {
+        let handler = interrupted as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    });
+}
+
+// Drain a pipe on a separate thread, so a chatty command can't fill it up and block
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    // The child leads its process group, see `output`
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+}
+
+// The slot of a running command in `GROUPS`, freed once the command is done.
+// With every slot taken, the command isn't killed along with rustlings.
+#[cfg_attr(not(unix), allow(dead_code))]
+struct Group(Option<usize>);
+
+impl Group {
+    #[cfg(unix)]
+    fn register(child: &Child) -> Group {
+        let pgid = child.id() as i32;
+        let free = |slot: &AtomicI32| {
+            slot.compare_exchange(0, pgid, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        };
+        Group(GROUPS.iter().position(free))
+    }
+
+    #[cfg(not(unix))]
+    fn register(_child: &Child) -> Group {
+        Group(None)
+    }
+}
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(slot) = self.0 {
+            GROUPS[slot].store(0, Ordering::SeqCst);
+        }
+    }
+}
+
+// Kill the running commands and restore the terminal, then die of the signal
+// like rustlings would have without the handler. Only async-signal-safe calls
+// are allowed here.
+#[cfg(unix)]
+extern "C" fn interrupted(signal: libc::c_int) {
+    if TERMINAL_TAKEN.load(Ordering::SeqCst) {
+        unsafe {
+            libc::write(
+                libc::STDOUT_FILENO,
+                RESTORE_TERMINAL.as_ptr().cast(),
+                RESTORE_TERMINAL.len(),
+            );
+        }
+    }
+    for slot in &GROUPS {
+        let pgid = slot.load(Ordering::SeqCst);
+        if pgid != 0 {
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
+    }
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_cancel_kills_command() {
+        let job = Job::spawn(|| output(Command::new("sh").args(["-c", "sleep 10 & sleep 10"])));
+        thread::sleep(Duration::from_millis(200));
+        let start = Instant::now();
+        job.cancel();
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_timeout_kills_command() {
+        let start = Instant::now();
+        let error = output_with(
+            Command::new("sh").args(["-c", "sleep 10 & sleep 10"]),
+            None,
+            Some(Duration::from_millis(200)),
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_output_with_input() {
+        let output = output_with(Command::new("cat").arg("-"), Some(b"some input"), None).unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"some input");
+    }
+
+    #[test]
+    fn test_output_in_job() {
+        let output = Job::spawn(|| output(Command::new("rustc").arg("--version")))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("rustc"));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::exercise::{Exercise, Mode};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// The hidden directory the workspace is generated in
+pub const WORKSPACE_DIR: &str = ".rustlings";
+// Where the packages are placed in the workspace, relative to it
+const PACKAGES_DIR: &str = "exercises";
+// Where the clippy exercises are linted, each in a package of its own
+const CLIPPY_DIR: &str = "clippy";
+// Where the exercises with a build script are tested, each in a package of its own
+const BUILD_SCRIPT_DIR: &str = "build_script";
+
+// The path of a file of the exercises, seen from the manifest of a package.
+// Packages are two levels below the workspace, which is at the root.
+fn from_package(path: &Path) -> String {
+    Path::new("../../..")
+        .join(path)
+        .display()
+        .to_string()
+        .replace('\\', "/")
+}
+
+// The manifest of the package of an exercise. Its single target points at the
+// exercise file, and is a test target for the exercises run as tests, so that
+// they don't need a `main` function.
+pub fn manifest(exercise: &Exercise) -> String {
+    let mut manifest = format!(
+        r#"[package]
+name = "{}"
+version = "0.0.0"
+edition = "{}"
+publish = false
+"#,
+        exercise.name,
+        exercise.edition()
+    );
+    if let Some(version) = exercise.build.min_rust_version {
+        let _ = writeln!(manifest, "rust-version = \"{version}\"");
+    }
+    if exercise.mode == Mode::BuildScript {
+        if let Some(dir) = exercise.path.parent() {
+            let _ = writeln!(manifest, "build = \"{}\"", from_package(&dir.join("build.rs")));
+        }
+    }
+    if exercise.mode == Mode::Clippy {
+        // The lints `verify` checks the clippy exercises for
+        manifest.push_str("\n[lints.rust]\nwarnings = \"deny\"\n\n[lints.clippy]\n");
+        for (lint, level) in exercise.clippy_lints() {
+            let lint = lint.trim_start_matches("clippy::");
+            let _ = writeln!(manifest, "{lint} = \"{}\"", level.as_str());
+        }
+    }
+    // A package has a single library target, and any number of the others
+    let target = match exercise.mode {
+        Mode::Test | Mode::Bench => "[[test]]",
+        Mode::Doctest => "[lib]",
+        Mode::Compile | Mode::Clippy | Mode::BuildScript | Mode::Output | Mode::CompileFail => {
+            "[[bin]]"
+        }
+    };
+    let _ = write!(
+        manifest,
+        "\n{target}\nname = \"{}\"\npath = \"{}\"\n",
+        exercise.name,
+        from_package(&exercise.path)
+    );
+    manifest
+}
+
+// Generate a Cargo workspace with a package for every exercise in the hidden
+// `.rustlings` directory, so that Cargo and IDEs work on the exercises as they
+// would on any other project. Returns the path of the workspace manifest.
+pub fn generate(exercises: &[Exercise]) -> io::Result<PathBuf> {
+    let workspace = create_hidden_dir()?;
+    let packages = workspace.join(PACKAGES_DIR);
+    // Start over, as exercises may have been renamed or removed from info.toml
+    if packages.exists() {
+        fs::remove_dir_all(&packages)?;
+    }
+
+    let mut members = String::new();
+    for exercise in exercises {
+        let package = packages.join(&exercise.name);
+        fs::create_dir_all(&package)?;
+        fs::write(package.join("Cargo.toml"), manifest(exercise))?;
+        let _ = writeln!(members, "    \"{PACKAGES_DIR}/{}\",", exercise.name);
+    }
+
+    let manifest_path = workspace.join("Cargo.toml");
+    fs::write(
+        &manifest_path,
+        format!(
+            "# Generated by `rustlings workspace`, changes will be overwritten.\n\
+             [workspace]\nresolver = \"2\"\nmembers = [\n{members}]\n"
+        ),
+    )?;
+    Ok(manifest_path)
+}
+
+// Create the hidden directory, keeping everything generated in it out of
+// the learner's commits
+fn create_hidden_dir() -> io::Result<PathBuf> {
+    let workspace = PathBuf::from(WORKSPACE_DIR);
+    fs::create_dir_all(&workspace)?;
+    fs::write(workspace.join(".gitignore"), "*\n")?;
+    Ok(workspace)
+}
+
+// The directory of the package a clippy or build script exercise is
+// compiled in with Cargo
+pub fn package_dir(exercise: &Exercise) -> PathBuf {
+    let dir = match exercise.mode {
+        Mode::BuildScript => BUILD_SCRIPT_DIR,
+        _ => CLIPPY_DIR,
+    };
+    Path::new(WORKSPACE_DIR).join(dir).join(&exercise.name)
+}
+
+// Create the package a clippy or build script exercise is compiled in, with
+// its own target directory. Being a workspace of its own, it's independent of
+// the generated workspace and of the other exercises, even when they are
+// compiled at the same time.
+pub fn package(exercise: &Exercise) -> io::Result<PathBuf> {
+    create_hidden_dir()?;
+    let package = package_dir(exercise);
+    fs::create_dir_all(&package)?;
+    let manifest = manifest(exercise) + "\n[workspace]\n";
+    // Leave an unchanged manifest alone, so Cargo doesn't rebuild for it
+    if fs::read_to_string(package.join("Cargo.toml")).ok().as_deref() != Some(manifest.as_str()) {
+        fs::write(package.join("Cargo.toml"), manifest)?;
+    }
+    Ok(package)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::toolchain::Version;
+
+    #[test]
+    fn test_manifest() {
+        let mut exercise =
+            Exercise::for_test("tests7", "exercises/tests/tests7.rs", Mode::BuildScript);
+        exercise.build.min_rust_version = Version::parse("1.70");
+        let build_script = manifest(&exercise);
+        assert!(build_script.contains("rust-version = \"1.70.0\""));
+        assert!(build_script.contains("build = \"../../../exercises/tests/build.rs\""));
+        assert!(build_script.contains("[[bin]]\nname = \"tests7\"\npath = \"../../../exercises/tests/tests7.rs\""));
+
+        exercise.mode = Mode::Test;
+        let test = manifest(&exercise);
+        assert!(test.contains("[[test]]"));
+        assert!(!test.contains("build ="));
+
+        exercise.mode = Mode::Clippy;
+        let clippy = manifest(&exercise);
+        assert!(clippy.contains("[lints.clippy]\nfloat_cmp = \"deny\"\n"));
+    }
+}
@@ -0,0 +1,10 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+// Counts its runs in the file named by `RUNS_FILE`
+fn main() {
+    if let Ok(path) = std::env::var("RUNS_FILE") {
+        let mut runs = OpenOptions::new().create(true).append(true).open(path).unwrap();
+        writeln!(runs, "run").unwrap();
+    }
+}
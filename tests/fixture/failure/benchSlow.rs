@@ -0,0 +1,9 @@
+fn sum(n: usize) -> usize {
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    (1..=n).sum()
+}
+
+#[test]
+fn sums() {
+    assert_eq!(sum(4), 10);
+}
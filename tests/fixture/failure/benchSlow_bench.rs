@@ -0,0 +1,3 @@
+fn workload(n: usize) {
+    assert_eq!(sum(n), n * (n + 1) / 2);
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let vec0 = vec![1, 2, 3];
+    let vec1 = vec0;
+    println!("{:?} {:?}", vec0, vec1);
+}
@@ -0,0 +1,13 @@
+/// ```
+/// assert_eq!(doc_failure::add_one(1), 2);
+/// ```
+pub fn add_one(x: i32) -> i32 {
+    x + 1
+}
+
+/// ```
+/// assert_eq!(doc_failure::two(), 3);
+/// ```
+pub fn two() -> i32 {
+    2
+}
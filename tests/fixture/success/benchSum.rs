@@ -0,0 +1,8 @@
+fn sum(n: usize) -> usize {
+    (1..=n).sum()
+}
+
+#[test]
+fn sums() {
+    assert_eq!(sum(4), 10);
+}
@@ -0,0 +1,9 @@
+use std::io::Read;
+
+fn main() {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let name = std::env::var("NAME").unwrap();
+    println!("{} {} {}", input.trim(), args.join(","), name);
+}
@@ -90,6 +90,21 @@ fn run_single_test_not_passed() {
         .code(1);
 }
 
+#[test]
+fn run_single_compile_failure_shows_diagnostic_hint() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "compMovedValue"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(
+            predicates::str::contains("E0382")
+                .and(predicates::str::contains("The vector was moved into `vec1`."))
+                .and(predicates::str::contains("compFailure")),
+        );
+}
+
 #[test]
 fn run_single_test_no_filename() {
     Command::cargo_bin("rustlings")
@@ -110,6 +125,27 @@ fn run_single_test_no_exercise() {
         .code(1);
 }
 
+#[test]
+fn run_single_bench_within_budget() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["run", "benchSum"])
+        .current_dir("tests/fixture/success/")
+        .assert()
+        .success();
+}
+
+#[test]
+fn run_single_bench_over_budget() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(&["run", "benchSlow"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("10 ms"));
+}
+
 #[test]
 fn reset_single_exercise() {
     Command::cargo_bin("rustlings")